@@ -0,0 +1,73 @@
+use std::{
+    env,
+    fs::File,
+    io::{Read, Write},
+    path::{Path, PathBuf},
+};
+use walkdir::WalkDir;
+
+
+/// Name of the generated file in `OUT_DIR`.
+pub const OCL_SRC_LIST_FILE: &str = "ocl_src_list.rs";
+
+/// Rust source of `OCL_SRC_LIST` constant with `(path, content)` pairs
+/// of the files of `dir`, the paths are relative to it.
+///
+/// The directory entries are printed as `cargo:rerun-if-changed` lines.
+pub fn ocl_src_list<P: AsRef<Path>>(dir: P) -> String {
+    let ocl_src_dir = dir.as_ref();
+
+    let mut files = Vec::new();
+    for entry in WalkDir::new(ocl_src_dir).into_iter().map(|e| e.unwrap()) {
+        println!("cargo:rerun-if-changed={}", entry.path().display());
+        if entry.file_type().is_file() {
+            files.push(entry.into_path());
+        }
+    }
+    let lines = files.into_iter().map(|path| {
+        let mut content = String::new();
+        File::open(&path).unwrap().read_to_string(&mut content).unwrap();
+        format!(
+            "\t(r#\"{}\"#, r###\"{}\"###),",
+            path.strip_prefix(ocl_src_dir).unwrap().display(),
+            content,
+        )
+    }).collect::<Vec<_>>();
+    [
+        format!("const OCL_SRC_LIST: [(&str, &str); {}] = [", lines.len()),
+        lines.join("\n"),
+        "];".to_string(),
+    ].join("\n")
+}
+
+/// Embeds the files of `ocl-src` directory of the crate, should be called from `build.rs`.
+///
+/// The list is written to `OUT_DIR` and could be included with
+/// `include!(concat!(env!("OUT_DIR"), "/ocl_src_list.rs"))`.
+pub fn embed_ocl_src() {
+    let text = ocl_src_list("ocl-src");
+
+    let out_dir = PathBuf::from(env::var("OUT_DIR").unwrap());
+    let mut out_file = File::create(out_dir.join(OCL_SRC_LIST_FILE)).unwrap();
+
+    out_file.write_all(text.as_bytes()).unwrap();
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn list() {
+        let dir = env::temp_dir().join(format!("clay_build_test_{}", std::process::id()));
+        fs::create_dir_all(dir.join("shape")).unwrap();
+        fs::write(dir.join("shape/ball.h"), "#pragma once\n").unwrap();
+
+        let text = ocl_src_list(&dir);
+        assert!(text.starts_with("const OCL_SRC_LIST: [(&str, &str); 1] = ["));
+        assert!(text.contains("(r#\"shape/ball.h\"#, r###\"#pragma once\n\"###),"));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,34 @@
+#pragma once
+
+
+// Marks pixels which relative error of the mean color exceeds the threshold
+// and counts them in `unconverged`.
+__kernel void adapt(
+    int2 size,
+    int n_passes,
+    float threshold,
+    uint min_samples,
+    __global const float *color_buffer,
+    __global const float *squares,
+    __global const uint *sample_count,
+    __global uchar *mask,
+    __global uint *unconverged
+) {
+    int2 pos = (int2)(get_global_id(0), get_global_id(1));
+    int idx = pos.x + pos.y*size.x;
+
+    uint n = sample_count[idx];
+    bool active = true;
+    if (n >= max(min_samples, 2u) && n_passes > 0) {
+        float3 mean = vload3(idx, color_buffer)/n_passes;
+        float3 var = fmax(vload3(idx, squares)/n - mean*mean, 0.0f);
+        float error = sqrt((var.x + var.y + var.z)/(3.0f*n));
+        float level = (mean.x + mean.y + mean.z)/3.0f;
+        active = error > threshold*(level + 1e-2f);
+    }
+
+    mask[idx] = active;
+    if (active) {
+        atomic_inc(unconverged);
+    }
+}
@@ -0,0 +1,59 @@
+#pragma once
+
+
+// B3-spline filter taps
+__constant float DENOISE_TAPS[5] = { 1.0f/16, 1.0f/4, 3.0f/8, 1.0f/4, 1.0f/16 };
+
+// Single iteration of the edge-avoiding a-trous wavelet filter.
+// Values of `src` are multiplied by `scale`, feature buffers by `feature_scale`.
+// Feature buffers are optional, missing features don't affect the weights.
+__kernel void denoise(
+    int2 size,
+    int step,
+    float scale,
+    float feature_scale,
+    float4 sigma, // color, normal, depth, albedo
+    __global const float *src,
+    __global float *dst,
+    __global const float *normal,
+    __global const float *depth,
+    __global const float *albedo
+) {
+    int2 pos = (int2)(get_global_id(0), get_global_id(1));
+    int idx = pos.x + pos.y*size.x;
+
+    float3 c0 = scale*vload3(idx, src);
+    float3 n0 = normal ? feature_scale*vload3(idx, normal) : (float3)(0.0f);
+    float d0 = depth ? feature_scale*depth[idx] : 0.0f;
+    float3 a0 = albedo ? feature_scale*vload3(idx, albedo) : (float3)(0.0f);
+
+    float3 sum = (float3)(0.0f);
+    float wsum = 0.0f;
+    for (int j = -2; j <= 2; ++j) {
+        for (int i = -2; i <= 2; ++i) {
+            int2 q = clamp(pos + step*(int2)(i, j), (int2)(0), size - 1);
+            int qidx = q.x + q.y*size.x;
+
+            float3 c = scale*vload3(qidx, src);
+            float3 dc = c - c0;
+            float e = dot(dc, dc)/sigma.x;
+            if (normal) {
+                float3 dn = feature_scale*vload3(qidx, normal) - n0;
+                e += dot(dn, dn)/sigma.y;
+            }
+            if (depth) {
+                float dd = (feature_scale*depth[qidx] - d0)/(step*fmax(d0, 1e-4f));
+                e += dd*dd/sigma.z;
+            }
+            if (albedo) {
+                float3 da = feature_scale*vload3(qidx, albedo) - a0;
+                e += dot(da, da)/sigma.w;
+            }
+
+            float w = DENOISE_TAPS[i + 2]*DENOISE_TAPS[j + 2]*exp(-e);
+            sum += w*c;
+            wsum += w;
+        }
+    }
+    vstore3(sum/wsum, idx, dst);
+}
@@ -1,3 +1,7 @@
+#include <clay_core/denoise.h>
+#include <clay_core/adaptive.h>
+
+
 __kernel void draw(
     int2 size,
     int n_passes,
@@ -1,18 +1,22 @@
 #pragma once
 
-#define MAP_RET float3
-#define MAP_RET_BAD (float3)(0.0f)
+#include <clay_core/space/space.h>
 
+// Maps are applied to both points (`abs`) and vectors (`rel`, `norm`) of the space
+#define MAP_RET space_vector
+#define MAP_RET_BAD (space_vector)(0.0f)
+
+// Maps could be animated, `t` is the time of the ray
 #define MAP_ARGS_DEF \
-    float3 v, \
+    space_vector v, float t, \
     __global const int *ibuf, \
     __global const float *fbuf
 
 #define MAP_ARGS \
-    v, ibuf, fbuf
+    v, t, ibuf, fbuf
 
 #define MAP_ARGS_VB(v, di, df) \
-    (v), ibuf + (di), fbuf + (df)
+    (v), t, ibuf + (di), fbuf + (df)
 
 #define MAP_ARGS_V(v) \
     MAP_ARGS_VB(v, 0, 0)
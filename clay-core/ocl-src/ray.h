@@ -1,26 +1,32 @@
 #pragma once
 
+#include <clay_core/space/space.h>
+
 #define RAY_INITIAL  0
 #define RAY_DIFFUSE  (1<<0)
 #define RAY_TARGETED (1<<1)
 
 typedef struct {
-    float3 start;
-    float3 dir;
+    space_point start;
+    // Unit direction at the start point
+    space_vector dir;
     float3 color;
     uint history;
     int origin;
     int target;
+    // Moment within the shutter interval, kept through the bounces
+    float time;
 } Ray;
 
 Ray ray_new() {
     Ray r = {
-        .start = (float3)(0.0f),
-        .dir   = (float3)(0.0f),
+        .start = (space_point)(0.0f),
+        .dir   = (space_vector)(0.0f),
         .color = (float3)(0.0f),
         .history = RAY_INITIAL,
         .origin = -1,
-        .target = -1
+        .target = -1,
+        .time = 0.0f
     };
     return r;
 }
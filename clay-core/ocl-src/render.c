@@ -1,22 +1,87 @@
 #include <clay_core/ray.h>
+#include <clay_core/random.h>
+#include <clay_core/trace.h>
 #include <__gen/scene.h>
 #include <__gen/view.h>
+#include <__gen/aov.h>
 
 
 __kernel void render(
     int2 size,
+    int2 offset,
+    int2 frame,
     __global float *color_buffer,
     __global uint *random,
+    __global uint *ray_count, // optional, could be null
+    // AOV buffers, used only if enabled in `__gen/aov.h`
+    __global float *aov_depth,
+    __global float *aov_normal,
+    __global float *aov_albedo,
+    __global float *aov_object_id,
+    __global float *aov_samples,
+    // Adaptive sampling
+    int n_passes, // number of already accumulated passes
+    __global float *squares,
+    __global uint *sample_count,
+    __global const uchar *mask, // optional, all pixels are traced if null
+    // Motion blur
+    float2 shutter, // open and close time
     VIEW_ARGS_DEF,
     SCENE_ARGS_DEF
 ) {
     int2 pos = (int2)(get_global_id(0), get_global_id(1));
     int idx = pos.x + pos.y*size.x;
+
+    // A stale mask could be left after the screen is cleared, there is no mean to accumulate then
+    if (mask && n_passes > 0 && !mask[idx]) {
+        // Converged pixel accumulates its current mean instead of a new sample,
+        // so the values averaged over passes stay valid
+        float k = 1.0f + 1.0f/n_passes;
+        vstore3(k*vload3(idx, color_buffer), idx, color_buffer);
+#ifdef AOV_DEPTH
+        aov_depth[idx] *= k;
+#endif
+#ifdef AOV_NORMAL
+        vstore3(k*vload3(idx, aov_normal), idx, aov_normal);
+#endif
+#ifdef AOV_ALBEDO
+        vstore3(k*vload3(idx, aov_albedo), idx, aov_albedo);
+#endif
+        return;
+    }
+
     uint seed = random[idx];
 
-    Ray ray = __view_emit(&seed, pos, size, VIEW_ARGS);
-    float3 color = __scene_trace(&seed, ray, SCENE_ARGS);
+    // Screen could be a tile of the larger frame
+    Ray ray = __view_emit(&seed, offset + pos, frame, VIEW_ARGS);
+    ray.time = shutter.x;
+    if (shutter.y > shutter.x) {
+        ray.time += (shutter.y - shutter.x)*random_uniform(&seed);
+    }
+    TraceInfo info = trace_info_new();
+    float3 color = __scene_trace(&seed, ray, &info, SCENE_ARGS);
+    if (ray_count) {
+        ray_count[idx] += info.rays;
+    }
 
     random[idx] = seed;
     vstore3(vload3(idx, color_buffer) + color, idx, color_buffer);
+    vstore3(vload3(idx, squares) + color*color, idx, squares);
+    sample_count[idx] += 1;
+
+#ifdef AOV_DEPTH
+    aov_depth[idx] += info.depth;
+#endif
+#ifdef AOV_NORMAL
+    vstore3(vload3(idx, aov_normal) + info.normal, idx, aov_normal);
+#endif
+#ifdef AOV_ALBEDO
+    vstore3(vload3(idx, aov_albedo) + info.albedo, idx, aov_albedo);
+#endif
+#ifdef AOV_OBJECT_ID
+    aov_object_id[idx] = (float)info.object;
+#endif
+#ifdef AOV_SAMPLES
+    aov_samples[idx] += 1.0f;
+#endif
 }
@@ -1,21 +1,27 @@
 #pragma once
 
+#include <clay_core/space/space.h>
 #include <clay_core/map/map.h>
 #include <clay_core/shape/shape.h>
 
 
+// Ray and normal are moved with the mapping macros of the space,
+// so the same mapper is used in every space.
 #define MAP_SHAPE_FN_DEF(map_shape, shape, map, sdi, sdf) \
     SHAPE_HIT_RET map_shape##_hit(SHAPE_HIT_ARGS_DEF) { \
         Ray new_ray = ray; \
+        float t = ray.time; \
         new_ray.start = map##_abs_inv(MAP_ARGS_VB(ray.start, sdi, sdf)); \
-        float3 new_dir = map##_rel_inv(MAP_ARGS_VB(ray.dir, sdi, sdf)); \
+        space_vector new_dir = SPACE_MAP_DIR_INV(map, ray.start, ray.dir, new_ray.start, sdi, sdf); \
         float lenf = 1.0f/length(new_dir); \
         new_ray.dir = new_dir*lenf; \
         SHAPE_HIT_RET ret = shape##_hit(SHAPE_HIT_ARGS_R(new_ray)); \
         if (ret) { \
+            space_point pos = space_advance(new_ray.start, new_ray.dir, *enter); \
+            space_point new_pos = map##_abs(MAP_ARGS_VB(pos, sdi, sdf)); \
             *enter *= lenf; \
             *exit *= lenf; \
-            *norm = normalize(map##_norm(MAP_ARGS_VB(*norm, sdi, sdf))); \
+            *norm = normalize(SPACE_MAP_NORM(map, pos, *norm, new_pos, sdi, sdf)); \
         } \
         return ret; \
     }
@@ -0,0 +1,29 @@
+#pragma once
+
+// Ordinary flat space, points and vectors are `float3`.
+
+typedef float3 space_point;
+typedef float3 space_vector;
+
+space_point space_advance(space_point x, space_vector d, float t) {
+    return x + d*t;
+}
+
+float space_distance(space_point x, space_point y) {
+    return length(y - x);
+}
+
+space_vector space_transport(space_point x, space_vector v, space_point y) {
+    return v;
+}
+
+space_vector space_background_dir(space_point x, space_vector d) {
+    return d;
+}
+
+// Maps need not to be isometries, vectors and normals are transformed by their own methods
+#define SPACE_MAP_DIR_INV(map, x, d, new_x, di, df) \
+    map##_rel_inv(MAP_ARGS_VB(d, di, df))
+
+#define SPACE_MAP_NORM(map, x, n, new_x, di, df) \
+    map##_norm(MAP_ARGS_VB(n, di, df))
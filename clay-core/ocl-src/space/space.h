@@ -0,0 +1,27 @@
+#pragma once
+
+// Geometry of the space where rays are traced.
+//
+// The header of the space is selected by the scene and included through `__gen/space.h`.
+// It must provide the following types and functions:
+//
+// + `space_point` and `space_vector` - representation of points and tangent vectors.
+//   Maps take both of them by the same argument, so they must be of the same type.
+// + `space_point space_advance(space_point x, space_vector d, float t)` -
+//   point at the distance `t` along the geodesic from `x` in the unit direction `d`.
+// + `float space_distance(space_point x, space_point y)` - distance between points.
+// + `space_vector space_transport(space_point x, space_vector v, space_point y)` -
+//   parallel transport of the vector `v` at `x` along the geodesic to `y`.
+// + `space_vector space_background_dir(space_point x, space_vector d)` - unit direction
+//   the background is looked up by for the ray from `x` in the direction `d`.
+// + `SPACE_MAP_DIR_INV(map, x, d, new_x, di, df)` - direction `d` at `x` moved by the inverse
+//   of the map to `new_x`. The length of the result is the ratio of new distances to the original ones.
+// + `SPACE_MAP_NORM(map, x, n, new_x, di, df)` - normal `n` at `x` moved by the map to `new_x`.
+//
+// Closed spaces where rays return to their start also define `SPACE_RETURN_EPS` -
+// the minimal distance the ray could hit the object it started from.
+//
+// The mapping macros are expanded inside functions taking `MAP_ARGS_DEF`-like arguments:
+// `t`, `ibuf` and `fbuf` must be in scope.
+
+#include <__gen/space.h>
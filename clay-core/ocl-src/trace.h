@@ -0,0 +1,43 @@
+#pragma once
+
+
+// Information collected while tracing a single path
+typedef struct {
+    // Number of traced ray segments
+    uint rays;
+    // Properties of the first hit, valid only if `hit` is set
+    bool hit;
+    float depth;
+    float3 normal;
+    float3 albedo;
+    int object;
+} TraceInfo;
+
+TraceInfo trace_info_new() {
+    TraceInfo info = {
+        .rays = 0,
+        .hit = false,
+        .depth = 0.0f,
+        .normal = (float3)(0.0f),
+        .albedo = (float3)(0.0f),
+        .object = -1,
+    };
+    return info;
+}
+
+// Records the hit if it is the first one on the path
+void trace_info_hit(TraceInfo *info, float depth, float3 normal, int object) {
+    if (info->rays == 1) {
+        info->hit = true;
+        info->depth = depth;
+        info->normal = normalize(normal);
+        info->object = object;
+    }
+}
+
+// Records the color of the first hit material
+void trace_info_albedo(TraceInfo *info, float3 albedo) {
+    if (info->rays == 1) {
+        info->albedo = albedo;
+    }
+}
@@ -0,0 +1,286 @@
+use std::{
+    fs,
+    io::Write,
+    path::Path,
+};
+use nalgebra::{Vector3, Rotation3, UnitQuaternion, Quaternion};
+
+
+/// Value that could be interpolated between keyframes and stored as a list of numbers.
+pub trait Key: Clone {
+    /// Interpolates between `a` and `b`, `t` is in `[0, 1]`.
+    fn lerp(a: &Self, b: &Self, t: f64) -> Self;
+
+    /// Interpolates between `p1` and `p2` using neighbouring keys `p0` and `p3`.
+    fn catmull_rom(p0: &Self, p1: &Self, p2: &Self, p3: &Self, t: f64) -> Self;
+
+    fn values(&self) -> Vec<f64>;
+    fn from_values(values: &[f64]) -> Option<Self>;
+}
+
+fn catmull_rom_f64(p0: f64, p1: f64, p2: f64, p3: f64, t: f64) -> f64 {
+    let (t2, t3) = (t*t, t*t*t);
+    0.5*(
+        2.0*p1 +
+        (p2 - p0)*t +
+        (2.0*p0 - 5.0*p1 + 4.0*p2 - p3)*t2 +
+        (3.0*p1 - p0 - 3.0*p2 + p3)*t3
+    )
+}
+
+impl Key for f64 {
+    fn lerp(a: &Self, b: &Self, t: f64) -> Self {
+        a + (b - a)*t
+    }
+    fn catmull_rom(p0: &Self, p1: &Self, p2: &Self, p3: &Self, t: f64) -> Self {
+        catmull_rom_f64(*p0, *p1, *p2, *p3, t)
+    }
+    fn values(&self) -> Vec<f64> {
+        vec![*self]
+    }
+    fn from_values(values: &[f64]) -> Option<Self> {
+        match values {
+            [x] => Some(*x),
+            _ => None,
+        }
+    }
+}
+
+impl Key for Vector3<f64> {
+    fn lerp(a: &Self, b: &Self, t: f64) -> Self {
+        a + (b - a)*t
+    }
+    fn catmull_rom(p0: &Self, p1: &Self, p2: &Self, p3: &Self, t: f64) -> Self {
+        Vector3::from_fn(|i, _| catmull_rom_f64(p0[i], p1[i], p2[i], p3[i], t))
+    }
+    fn values(&self) -> Vec<f64> {
+        self.as_slice().to_vec()
+    }
+    fn from_values(values: &[f64]) -> Option<Self> {
+        match values {
+            [x, y, z] => Some(Vector3::new(*x, *y, *z)),
+            _ => None,
+        }
+    }
+}
+
+/// Rotations are interpolated by slerp along the shortest path,
+/// the neighbouring keys are ignored.
+impl Key for UnitQuaternion<f64> {
+    fn lerp(a: &Self, b: &Self, t: f64) -> Self {
+        let b = if a.coords.dot(&b.coords) < 0.0 {
+            UnitQuaternion::new_unchecked(-b.into_inner())
+        } else {
+            *b
+        };
+        a.try_slerp(&b, t, 1e-9).unwrap_or_else(|| a.nlerp(&b, t))
+    }
+    fn catmull_rom(_: &Self, p1: &Self, p2: &Self, _: &Self, t: f64) -> Self {
+        <Self as Key>::lerp(p1, p2, t)
+    }
+    fn values(&self) -> Vec<f64> {
+        vec![self.w, self.i, self.j, self.k]
+    }
+    fn from_values(values: &[f64]) -> Option<Self> {
+        match values {
+            [w, i, j, k] => Some(UnitQuaternion::from_quaternion(Quaternion::new(*w, *i, *j, *k))),
+            _ => None,
+        }
+    }
+}
+
+/// Camera position and orientation.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Camera {
+    pub pos: Vector3<f64>,
+    pub ori: UnitQuaternion<f64>,
+}
+
+impl Camera {
+    pub fn new(pos: Vector3<f64>, map: Rotation3<f64>) -> Self {
+        Self { pos, ori: UnitQuaternion::from_rotation_matrix(&map) }
+    }
+    pub fn map(&self) -> Rotation3<f64> {
+        self.ori.to_rotation_matrix()
+    }
+}
+
+impl Key for Camera {
+    fn lerp(a: &Self, b: &Self, t: f64) -> Self {
+        Self { pos: Key::lerp(&a.pos, &b.pos, t), ori: Key::lerp(&a.ori, &b.ori, t) }
+    }
+    fn catmull_rom(p0: &Self, p1: &Self, p2: &Self, p3: &Self, t: f64) -> Self {
+        Self {
+            pos: Key::catmull_rom(&p0.pos, &p1.pos, &p2.pos, &p3.pos, t),
+            ori: Key::catmull_rom(&p0.ori, &p1.ori, &p2.ori, &p3.ori, t),
+        }
+    }
+    fn values(&self) -> Vec<f64> {
+        [self.pos.values(), self.ori.values()].concat()
+    }
+    fn from_values(values: &[f64]) -> Option<Self> {
+        if values.len() != 7 {
+            return None;
+        }
+        Some(Self {
+            pos: Key::from_values(&values[..3])?,
+            ori: Key::from_values(&values[3..])?,
+        })
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Interpolation {
+    Linear,
+    CatmullRom,
+}
+
+impl Interpolation {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Interpolation::Linear => "linear",
+            Interpolation::CatmullRom => "catmull_rom",
+        }
+    }
+}
+
+/// Sequence of keyframes sorted by time.
+///
+/// The track could be used for camera (`Track<Camera>`) as well as for object transforms
+/// (e.g. `Track<Vector3<f64>>` for position).
+#[derive(Clone, Debug, PartialEq)]
+pub struct Track<T: Key> {
+    keys: Vec<(f64, T)>,
+    pub interpolation: Interpolation,
+}
+
+impl<T: Key> Track<T> {
+    pub fn new(interpolation: Interpolation) -> Self {
+        Self { keys: Vec::new(), interpolation }
+    }
+
+    /// Inserts the keyframe keeping the keys sorted by time.
+    pub fn add(&mut self, time: f64, value: T) -> &mut Self {
+        let index = self.keys.iter().position(|(t, _)| *t > time).unwrap_or(self.keys.len());
+        self.keys.insert(index, (time, value));
+        self
+    }
+
+    pub fn keys(&self) -> &[(f64, T)] {
+        &self.keys
+    }
+    pub fn clear(&mut self) {
+        self.keys.clear();
+    }
+
+    /// Time range covered by the keys.
+    pub fn range(&self) -> Option<(f64, f64)> {
+        Some((self.keys.first()?.0, self.keys.last()?.0))
+    }
+
+    /// Interpolated value at the time, clamped to the range of the keys.
+    pub fn sample(&self, time: f64) -> Option<T> {
+        let (first, last) = self.range()?;
+        if time <= first {
+            return Some(self.keys[0].1.clone());
+        }
+        if time >= last {
+            return Some(self.keys[self.keys.len() - 1].1.clone());
+        }
+        let i = self.keys.iter().position(|(t, _)| *t > time).unwrap() - 1;
+        let ((t1, p1), (t2, p2)) = (&self.keys[i], &self.keys[i + 1]);
+        let t = if t2 > t1 { (time - t1)/(t2 - t1) } else { 0.0 };
+        Some(match self.interpolation {
+            Interpolation::Linear => T::lerp(p1, p2, t),
+            Interpolation::CatmullRom => {
+                // The end keys are repeated
+                let p0 = &self.keys[i.saturating_sub(1)].1;
+                let p3 = &self.keys[(i + 2).min(self.keys.len() - 1)].1;
+                T::catmull_rom(p0, p1, p2, p3, t)
+            },
+        })
+    }
+
+    /// Writes the track as text: the interpolation name in the first line
+    /// and the time followed by the values of each key in the next lines.
+    pub fn write<W: Write>(&self, writer: &mut W) -> crate::Result<()> {
+        writeln!(writer, "{}", self.interpolation.name())?;
+        for (time, value) in self.keys.iter() {
+            let values = value.values().iter().map(|x| x.to_string()).collect::<Vec<_>>();
+            writeln!(writer, "{} {}", time, values.join(" "))?;
+        }
+        Ok(())
+    }
+
+    pub fn parse(text: &str) -> crate::Result<Self> {
+        let mut lines = text.lines().map(|l| l.trim()).filter(|l| !l.is_empty());
+        let interpolation = match lines.next() {
+            Some("linear") => Interpolation::Linear,
+            Some("catmull_rom") => Interpolation::CatmullRom,
+            other => return Err(format!("unknown interpolation: {:?}", other).into()),
+        };
+        let mut track = Self::new(interpolation);
+        for line in lines {
+            let numbers = line.split_whitespace()
+            .map(|x| x.parse::<f64>())
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| format!("track key '{}': {}", line, e))?;
+            let value = numbers.get(1..).and_then(T::from_values)
+            .ok_or_else(|| format!("track key '{}': wrong number of values", line))?;
+            track.add(numbers[0], value);
+        }
+        Ok(track)
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> crate::Result<()> {
+        let mut text = Vec::new();
+        self.write(&mut text)?;
+        fs::write(path, text)?;
+        Ok(())
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> crate::Result<Self> {
+        Self::parse(&fs::read_to_string(path)?)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::f64::consts::PI;
+
+    #[test]
+    fn sample() {
+        let mut track = Track::new(Interpolation::Linear);
+        track.add(2.0, 4.0).add(0.0, 0.0).add(1.0, 1.0);
+        assert_eq!(track.sample(-1.0), Some(0.0));
+        assert_eq!(track.sample(1.5), Some(2.5));
+        assert_eq!(track.sample(3.0), Some(4.0));
+
+        // Catmull-Rom passes through the keys and is exact on the linear motion
+        let mut track = Track::new(Interpolation::CatmullRom);
+        for i in 0..4 {
+            track.add(i as f64, Vector3::new(i as f64, 0.0, 0.0));
+        }
+        assert!((track.sample(1.5).unwrap() - Vector3::new(1.5, 0.0, 0.0)).norm() < 1e-9);
+    }
+
+    #[test]
+    fn camera() {
+        let mut track = Track::new(Interpolation::CatmullRom);
+        track.add(0.0, Camera::new(Vector3::zeros(), Rotation3::identity()));
+        track.add(1.0, Camera::new(
+            Vector3::new(2.0, 0.0, 0.0),
+            Rotation3::from_axis_angle(&Vector3::z_axis(), 0.5*PI),
+        ));
+        let mid = track.sample(0.5).unwrap();
+        assert!((mid.ori.angle() - 0.25*PI).abs() < 1e-9);
+
+        let mut text = Vec::new();
+        track.write(&mut text).unwrap();
+        let parsed = Track::<Camera>::parse(&String::from_utf8(text).unwrap()).unwrap();
+        assert_eq!(parsed.interpolation, Interpolation::CatmullRom);
+        assert!((parsed.sample(0.5).unwrap().pos - mid.pos).norm() < 1e-9);
+        assert!(Track::<Camera>::parse("linear\n0 1 2").is_err());
+    }
+}
@@ -0,0 +1,55 @@
+/// Arbitrary output variable - additional per-pixel output of the render kernel.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Aov {
+    /// Distance to the first hit, zero if nothing is hit.
+    Depth,
+    /// Normal of the first hit surface.
+    Normal,
+    /// Color of the first hit material.
+    Albedo,
+    /// Index of the first hit object in the scene, `-1` if nothing is hit.
+    ObjectId,
+    /// Number of samples taken in the pixel.
+    Samples,
+}
+
+impl Aov {
+    /// All variables in the order of the render kernel arguments.
+    pub fn all() -> [Aov; 5] {
+        [Aov::Depth, Aov::Normal, Aov::Albedo, Aov::ObjectId, Aov::Samples]
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Aov::Depth => "depth",
+            Aov::Normal => "normal",
+            Aov::Albedo => "albedo",
+            Aov::ObjectId => "object_id",
+            Aov::Samples => "samples",
+        }
+    }
+
+    /// Number of float values per pixel.
+    pub fn channels(&self) -> usize {
+        match self {
+            Aov::Normal | Aov::Albedo => 3,
+            _ => 1,
+        }
+    }
+
+    /// Whether the value is accumulated over passes and should be averaged.
+    pub fn is_averaged(&self) -> bool {
+        match self {
+            Aov::Depth | Aov::Normal | Aov::Albedo => true,
+            Aov::ObjectId | Aov::Samples => false,
+        }
+    }
+}
+
+/// OpenCL definitions enabling the selected variables in the render kernel.
+pub fn aov_source(aovs: &[Aov]) -> String {
+    aovs.iter()
+    .map(|aov| format!("#define AOV_{}", aov.name().to_uppercase()))
+    .collect::<Vec<_>>()
+    .join("\n")
+}
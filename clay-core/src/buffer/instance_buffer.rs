@@ -49,36 +49,108 @@ impl<T: Pack> InstanceBuffer<T> {
     }
     */
 
+    fn pack_objects(objects: &[T]) -> (Vec<i32>, Vec<f32>) {
+        let (size_int, size_float) = (T::size_int(), T::size_float());
+        let mut buffer_int = vec![0i32; size_int*objects.len()];
+        let mut buffer_float = vec![0.0f32; size_float*objects.len()];
+        for (i, obj) in objects.iter().enumerate() {
+            obj.pack_to(
+                &mut buffer_int[(size_int*i)..(size_int*(i + 1))],
+                &mut buffer_float[(size_float*i)..(size_float*(i + 1))],
+            );
+        }
+        (buffer_int, buffer_float)
+    }
+
+    /// Writes the whole set of objects into the buffer.
+    ///
+    /// The number of objects *must* be equal to the buffer `count`.
     pub fn write(&mut self, objects: &[T]) -> crate::Result<()> {
-        let mut buffer_int = vec![0i32; T::size_int().max(1)*objects.len()];
-        let mut buffer_float = vec![0.0f32; T::size_float().max(1)*objects.len()];
-        // Use this `.max(1)` workaround because `chunks` panics on 0 (why there is such silly requirement?)
-        for (obj, (ibuf, fbuf)) in objects.iter().zip(
-            buffer_int.chunks_mut(Self::size_int().max(1))
-            .zip(buffer_float.chunks_mut(Self::size_float().max(1)))
-        ) {
-            obj.pack_to(&mut ibuf[..T::size_int()], &mut fbuf[..T::size_float()]);
+        if objects.len() == self.count {
+            self.write_at(0, objects)
+        } else {
+            Err("buffers size mismatch".into())
+        }
+    }
+
+    /// Writes objects into the buffer starting from the object with specified `index`.
+    ///
+    /// Objects outside of the `index..(index + objects.len())` range stay untouched.
+    pub fn write_at(&mut self, index: usize, objects: &[T]) -> crate::Result<()> {
+        if index.checked_add(objects.len()).map_or(true, |end| end > self.count) {
+            return Err(format!(
+                "objects range starting at {} of length {} is out of buffer bounds (count: {})",
+                index, objects.len(), self.count,
+            ).into());
         }
-        if objects.len() == 0 || T::size_int() == 0 { buffer_int = vec![0]; }
-        if objects.len() == 0 || T::size_float() == 0 { buffer_float = vec![0.0]; }
+        let (buffer_int, buffer_float) = Self::pack_objects(objects);
 
-        if buffer_int.len() == self.buffer_int.len() && buffer_float.len() == self.buffer_float.len() {
+        if !buffer_int.is_empty() {
             self.buffer_int.cmd()
-            .offset(0)
+            .offset(T::size_int()*index)
             .write(&buffer_int)
             .enq()?;
-
+        }
+        if !buffer_float.is_empty() {
             self.buffer_float.cmd()
-            .offset(0)
+            .offset(T::size_float()*index)
             .write(&buffer_float)
             .enq()?;
+        }
 
-            Ok(())
-        } else {
-            Err("buffers size mismatch".into())
+        Ok(())
+    }
+
+    /// Changes the number of objects the buffer is able to store.
+    ///
+    /// Contents of the first `min(count, self.count())` objects are preserved,
+    /// the rest of the buffer is filled with zeros.
+    pub fn resize(&mut self, context: &Context, count: usize) -> crate::Result<()> {
+        let buffer = Self::reserved(context, count)?;
+        let keep = count.min(self.count);
+
+        if T::size_int()*keep > 0 {
+            self.buffer_int.cmd()
+            .copy(&buffer.buffer_int, Some(0), Some(T::size_int()*keep))
+            .enq()?;
+        }
+        if T::size_float()*keep > 0 {
+            self.buffer_float.cmd()
+            .copy(&buffer.buffer_float, Some(0), Some(T::size_float()*keep))
+            .enq()?;
+        }
+
+        *self = buffer;
+        Ok(())
+    }
+
+    /// Reads packed objects back from the device.
+    ///
+    /// Returns a pair of int and float buffers for each object.
+    pub fn read(&self) -> crate::Result<Vec<(Vec<i32>, Vec<f32>)>> {
+        let (size_int, size_float) = (T::size_int(), T::size_float());
+        let mut buffer_int = vec![0i32; size_int*self.count];
+        let mut buffer_float = vec![0.0f32; size_float*self.count];
+
+        if !buffer_int.is_empty() {
+            self.buffer_int.cmd()
+            .offset(0)
+            .read(&mut buffer_int)
+            .enq()?;
+        }
+        if !buffer_float.is_empty() {
+            self.buffer_float.cmd()
+            .offset(0)
+            .read(&mut buffer_float)
+            .enq()?;
         }
+
+        Ok((0..self.count).map(|i| (
+            buffer_int[(size_int*i)..(size_int*(i + 1))].to_vec(),
+            buffer_float[(size_float*i)..(size_float*(i + 1))].to_vec(),
+        )).collect())
     }
-    
+
     pub fn buffer_int(&self) -> &ocl::Buffer<i32> {
         &self.buffer_int
     }
@@ -119,3 +191,70 @@ impl<T: Pack> Push for InstanceBuffer<T> {
         5
     }
 }
+
+
+#[cfg(test)]
+mod test {
+    use nalgebra::Vector3;
+    use crate::context::test_context;
+    use super::*;
+
+    fn vectors(n: usize, k: f64) -> Vec<Vector3<f64>> {
+        (0..n).map(|i| Vector3::new(i as f64, k, -(i as f64))).collect()
+    }
+
+    fn floats(buffer: &InstanceBuffer<Vector3<f64>>) -> Vec<Vec<f32>> {
+        buffer.read().unwrap().into_iter().map(|(bi, bf)| {
+            assert!(bi.is_empty());
+            bf
+        }).collect()
+    }
+
+    fn packed(v: &Vector3<f64>) -> Vec<f32> {
+        v.iter().map(|x| *x as f32).collect()
+    }
+
+    #[test]
+    fn write_at() {
+        let context = match test_context() { Some(c) => c, None => return };
+        let mut buffer = InstanceBuffer::new(&context, &vectors(4, 1.0)).unwrap();
+        buffer.write_at(1, &vectors(2, 2.0)).unwrap();
+        let data = floats(&buffer);
+        assert_eq!(data[0], packed(&Vector3::new(0.0, 1.0, 0.0)));
+        assert_eq!(data[1], packed(&Vector3::new(0.0, 2.0, 0.0)));
+        assert_eq!(data[2], packed(&Vector3::new(1.0, 2.0, -1.0)));
+        assert_eq!(data[3], packed(&Vector3::new(3.0, 1.0, -3.0)));
+
+        assert!(buffer.write_at(3, &vectors(2, 3.0)).is_err());
+        assert!(buffer.write_at(usize::max_value(), &vectors(1, 3.0)).is_err());
+        assert!(buffer.write(&vectors(3, 3.0)).is_err());
+    }
+
+    #[test]
+    fn write_at_int() {
+        let context = match test_context() { Some(c) => c, None => return };
+        let mut buffer = InstanceBuffer::new(&context, &[1i32, 2, 3]).unwrap();
+        buffer.write_at(2, &[5]).unwrap();
+        let data = buffer.read().unwrap();
+        assert_eq!(data.iter().map(|(bi, _)| bi[0]).collect::<Vec<_>>(), vec![1, 2, 5]);
+    }
+
+    #[test]
+    fn resize() {
+        let context = match test_context() { Some(c) => c, None => return };
+        let objects = vectors(3, 1.0);
+        let mut buffer = InstanceBuffer::new(&context, &objects).unwrap();
+
+        buffer.resize(&context, 5).unwrap();
+        assert_eq!(buffer.count(), 5);
+        let data = floats(&buffer);
+        for i in 0..3 {
+            assert_eq!(data[i], packed(&objects[i]));
+        }
+        assert_eq!(data[3], vec![0.0; 3]);
+        assert_eq!(data[4], vec![0.0; 3]);
+
+        buffer.resize(&context, 2).unwrap();
+        assert_eq!(floats(&buffer), vec![packed(&objects[0]), packed(&objects[1])]);
+    }
+}
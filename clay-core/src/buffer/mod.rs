@@ -1,6 +1,9 @@
 mod screen;
 pub use screen::Screen;
 
+mod multi_screen;
+pub use multi_screen::MultiScreen;
+
 mod instance_buffer;
 pub use instance_buffer::InstanceBuffer;
 
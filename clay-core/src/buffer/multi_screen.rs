@@ -0,0 +1,71 @@
+use crate::{MultiContext, buffer::Screen};
+
+
+/// Set of screens, one for each device of the `MultiContext`.
+///
+/// Each device accumulates its own passes, the results are merged on the host.
+pub struct MultiScreen {
+    screens: Vec<Screen>,
+    dims: (usize, usize),
+}
+
+impl MultiScreen {
+    pub fn new(context: &MultiContext, dims: (usize, usize)) -> crate::Result<Self> {
+        let screens = context.contexts().iter()
+        .map(|c| Screen::new(c, dims))
+        .collect::<crate::Result<Vec<_>>>()?;
+        Ok(Self { screens, dims })
+    }
+
+    /// Reads and sums accumulated colors of all screens.
+    pub fn read_color(&self) -> crate::Result<Vec<f32>> {
+        let len = 3*self.dims.0*self.dims.1;
+        let mut color = vec![0f32; len];
+        let mut buffer = vec![0f32; len];
+        for screen in self.screens.iter() {
+            screen.color().cmd()
+            .offset(0)
+            .read(&mut buffer)
+            .enq()?;
+            for (c, b) in color.iter_mut().zip(buffer.iter()) {
+                *c += *b;
+            }
+        }
+        Ok(color)
+    }
+
+    /// Merges the screens into RGB bytes the same way the `draw` kernel does.
+    pub fn read(&self) -> crate::Result<Vec<u8>> {
+        let n_passes = self.n_passes();
+        let color = self.read_color()?;
+        Ok(color.iter().map(|c| {
+            if n_passes > 0 {
+                (255.0*(c/n_passes as f32).clamp(0.0, 1.0)) as u8
+            } else {
+                0
+            }
+        }).collect())
+    }
+
+    pub fn clear(&mut self) -> crate::Result<()> {
+        for screen in self.screens.iter_mut() {
+            screen.clear()?;
+        }
+        Ok(())
+    }
+
+    pub fn screens(&self) -> &[Screen] {
+        &self.screens
+    }
+    pub fn screens_mut(&mut self) -> &mut [Screen] {
+        &mut self.screens
+    }
+    /// Total number of passes of all screens.
+    pub fn n_passes(&self) -> usize {
+        self.screens.iter().map(|s| s.n_passes()).sum()
+    }
+
+    pub fn dims(&self) -> (usize, usize) {
+        self.dims
+    }
+}
@@ -1,18 +1,42 @@
+use std::path::Path;
 use ocl;
 use rand::{Rng, thread_rng};
-use crate::Context;
+use crate::{Context, Aov, export::save_pfm};
 
 
 pub struct Screen {
     random: ocl::Buffer<u32>,
     color: ocl::Buffer<f32>,
     n_passes: usize,
-    bytes: ocl::Buffer<u8>,
+    /// Sum of squared color samples.
+    squares: ocl::Buffer<f32>,
+    /// Number of samples actually traced in each pixel.
+    sample_count: ocl::Buffer<u32>,
+    /// Pixels to trace in adaptive sampling mode.
+    mask: ocl::Buffer<u8>,
+    /// Number of pixels in the mask.
+    unconverged: ocl::Buffer<u32>,
+    /// Front and back image buffers, the draw writes to the back one and then swaps them,
+    /// so that the displayed image could be read while the next pass is rendered.
+    bytes: [ocl::Buffer<u8>; 2],
+    front: usize,
+    /// Draws that wrote the buffers.
+    drawn: [Option<ocl::Event>; 2],
+    /// Separate queue for reading the front buffer without waiting for enqueued passes.
+    read_queue: ocl::Queue,
+    aovs: Vec<(Aov, ocl::Buffer<f32>)>,
     dims: (usize, usize),
 }
 
 impl Screen {
     pub fn new(context: &Context, dims: (usize, usize)) -> crate::Result<Screen> {
+        Self::with_aovs(context, dims, &[])
+    }
+
+    /// Creates screen with buffers for the specified AOVs.
+    ///
+    /// The worker that renders to the screen must be built with the same AOVs.
+    pub fn with_aovs(context: &Context, dims: (usize, usize), aovs: &[Aov]) -> crate::Result<Screen> {
         let len = dims.0*dims.1;
 
         let random = ocl::Buffer::<u32>::builder()
@@ -35,26 +59,100 @@ impl Screen {
         .fill_val(0 as f32)
         .build()?;
 
-        let bytes = ocl::Buffer::<u8>::builder()
+        let squares = ocl::Buffer::<f32>::builder()
         .queue(context.queue().clone())
-        .flags(ocl::flags::MEM_WRITE_ONLY)
+        .flags(ocl::flags::MEM_READ_WRITE)
         .len(3*len)
-        .fill_val(0 as u8)
+        .fill_val(0 as f32)
+        .build()?;
+
+        let sample_count = ocl::Buffer::<u32>::builder()
+        .queue(context.queue().clone())
+        .flags(ocl::flags::MEM_READ_WRITE)
+        .len(len)
+        .fill_val(0 as u32)
+        .build()?;
+
+        let mask = ocl::Buffer::<u8>::builder()
+        .queue(context.queue().clone())
+        .flags(ocl::flags::MEM_READ_WRITE)
+        .len(len)
+        .fill_val(1 as u8)
         .build()?;
 
+        let unconverged = ocl::Buffer::<u32>::builder()
+        .queue(context.queue().clone())
+        .flags(ocl::flags::MEM_READ_WRITE)
+        .len(1)
+        .fill_val(len as u32)
+        .build()?;
+
+        let create_bytes = || {
+            ocl::Buffer::<u8>::builder()
+            .queue(context.queue().clone())
+            .flags(ocl::flags::MEM_READ_WRITE)
+            .len(3*len)
+            .fill_val(0 as u8)
+            .build()
+        };
+        let bytes = [create_bytes()?, create_bytes()?];
+        let read_queue = ocl::Queue::new(context.context(), *context.device(), None)?;
+
+        let aovs = aovs.iter().map(|aov| {
+            ocl::Buffer::<f32>::builder()
+            .queue(context.queue().clone())
+            .flags(ocl::flags::MEM_READ_WRITE)
+            .len(aov.channels()*len)
+            .fill_val(Self::aov_initial(*aov))
+            .build()
+            .map(|buffer| (*aov, buffer))
+        }).collect::<Result<Vec<_>, _>>()?;
+
         Ok(Screen {
             random,
             color, n_passes: 0,
-            bytes, dims,
+            squares, sample_count,
+            mask, unconverged,
+            bytes, front: 0, drawn: [None, None], read_queue,
+            aovs, dims,
         })
     }
     
+    /// Reads the image of the last enqueued draw waiting for it to complete.
     pub fn read(&self) -> crate::Result<Vec<u8>> {
-        let mut vec = vec![0 as u8; self.bytes.len()];
+        self.read_bytes(self.front)
+    }
 
-        self.bytes.cmd()
+    /// Reads the image of the last draw if it is complete, otherwise the image of the previous one.
+    ///
+    /// The pass enqueued after a complete one doesn't block the read, so the image could be
+    /// displayed while the device renders the next pass.
+    pub fn read_ready(&self) -> crate::Result<Vec<u8>> {
+        let complete = match &self.drawn[self.front] {
+            Some(event) => event.is_complete()?,
+            None => true,
+        };
+        if complete || self.drawn[1 - self.front].is_none() {
+            self.read_bytes(self.front)
+        } else {
+            self.read_bytes(1 - self.front)
+        }
+    }
+
+    fn read_bytes(&self, index: usize) -> crate::Result<Vec<u8>> {
+        let bytes = &self.bytes[index];
+        let mut vec = vec![0 as u8; bytes.len()];
+
+        // Only the draw of the buffer is waited for, the read queue is not blocked by other passes.
+        // The draw queue is flushed so that the draw is submitted to the device before the wait.
+        if let Some(queue) = bytes.default_queue() {
+            queue.flush()?;
+        }
+        bytes.cmd()
+        .queue(&self.read_queue)
         .offset(0)
         .read(&mut vec)
+        .ewait(self.drawn[index].as_ref())
         .enq()?;
 
         Ok(vec)
@@ -68,6 +166,28 @@ impl Screen {
         .offset(0)
         .fill(0f32, None)
         .enq()?;
+        self.squares.cmd()
+        .offset(0)
+        .fill(0f32, None)
+        .enq()?;
+        self.sample_count.cmd()
+        .offset(0)
+        .fill(0u32, None)
+        .enq()?;
+        self.mask.cmd()
+        .offset(0)
+        .fill(1u8, None)
+        .enq()?;
+        self.unconverged.cmd()
+        .offset(0)
+        .fill(self.len() as u32, None)
+        .enq()?;
+        for (aov, buffer) in self.aovs.iter() {
+            buffer.cmd()
+            .offset(0)
+            .fill(Self::aov_initial(*aov), None)
+            .enq()?;
+        }
 
         self.n_passes = 0;
         Ok(())
@@ -88,11 +208,93 @@ impl Screen {
     pub fn n_passes(&self) -> usize {
         self.n_passes
     }
+    pub fn squares(&self) -> &ocl::Buffer<f32> {
+        &self.squares
+    }
+    pub fn sample_count(&self) -> &ocl::Buffer<u32> {
+        &self.sample_count
+    }
+    pub fn mask(&self) -> &ocl::Buffer<u8> {
+        &self.mask
+    }
+    pub fn unconverged_buffer(&self) -> &ocl::Buffer<u32> {
+        &self.unconverged
+    }
+    /// Number of pixels not converged at the last adaptive pass,
+    /// all pixels are counted if adaptive sampling was not used.
+    pub fn unconverged(&self) -> crate::Result<usize> {
+        let mut count = [0u32];
+        self.unconverged.cmd()
+        .offset(0)
+        .read(&mut count[..])
+        .enq()?;
+        Ok(count[0] as usize)
+    }
+    /// Image buffer of the last draw.
     pub fn bytes(&self) -> &ocl::Buffer<u8> {
-        &self.bytes
+        &self.bytes[self.front]
+    }
+    /// Image buffer the next draw writes to.
+    pub fn back_bytes_mut(&mut self) -> &mut ocl::Buffer<u8> {
+        &mut self.bytes[1 - self.front]
+    }
+    /// Makes the back buffer written by the `draw` event the front one.
+    pub fn swap(&mut self, draw: ocl::Event) {
+        self.front = 1 - self.front;
+        self.drawn[self.front] = Some(draw);
+    }
+
+    fn aov_initial(aov: Aov) -> f32 {
+        match aov {
+            Aov::ObjectId => -1.0,
+            _ => 0.0,
+        }
     }
-    pub fn bytes_mut(&mut self) -> &mut ocl::Buffer<u8> {
-        &mut self.bytes
+
+    /// Buffer of the AOV, `None` if the screen is created without it.
+    pub fn aov(&self, aov: Aov) -> Option<&ocl::Buffer<f32>> {
+        self.aovs.iter().find(|(a, _)| *a == aov).map(|(_, b)| b)
+    }
+    pub fn aovs(&self) -> Vec<Aov> {
+        self.aovs.iter().map(|(a, _)| *a).collect()
+    }
+
+    fn read_buffer(&self, buffer: &ocl::Buffer<f32>, average: bool) -> crate::Result<Vec<f32>> {
+        let mut vec = vec![0f32; buffer.len()];
+        buffer.cmd()
+        .offset(0)
+        .read(&mut vec)
+        .enq()?;
+        if average && self.n_passes > 0 {
+            let k = 1.0/self.n_passes as f32;
+            vec.iter_mut().for_each(|x| *x *= k);
+        }
+        Ok(vec)
+    }
+
+    /// Reads color averaged over passes.
+    pub fn read_color(&self) -> crate::Result<Vec<f32>> {
+        self.read_buffer(&self.color, true)
+    }
+
+    /// Reads AOV values, the accumulated ones are averaged over passes.
+    pub fn read_aov(&self, aov: Aov) -> crate::Result<Vec<f32>> {
+        let buffer = self.aov(aov)
+        .ok_or_else(|| format!("screen has no buffer for '{}' AOV", aov.name()))?;
+        self.read_buffer(buffer, aov.is_averaged())
+    }
+
+    /// Saves color to `beauty.pfm` and each AOV to `<name>.pfm` in the directory.
+    pub fn save_pfm<P: AsRef<Path>>(&self, dir: P) -> crate::Result<()> {
+        let dir = dir.as_ref();
+        save_pfm(dir.join("beauty.pfm"), self.dims, 3, &self.read_color()?)?;
+        for (aov, _) in self.aovs.iter() {
+            save_pfm(
+                dir.join(format!("{}.pfm", aov.name())),
+                self.dims, aov.channels(), &self.read_aov(*aov)?,
+            )?;
+        }
+        Ok(())
     }
 
     pub fn dims(&self) -> (usize, usize) {
@@ -1,5 +1,7 @@
 use std::collections::HashSet;
 
+pub use clay_derive::Instance;
+
 
 /// An interface in OpenCL code.
 pub trait Class {
@@ -19,3 +21,27 @@ pub trait Instance<C: Class>: Sized + 'static {
     /// Name of the instance of the class (e.g. `sphere` as instance of class `shape`).
     fn inst_name() -> String;
 }
+
+#[cfg(test)]
+mod test {
+    use crate::{pack::*, map::MapClass, shape::ShapeClass};
+    use super::*;
+
+    #[allow(dead_code)]
+    #[derive(Pack, Instance)]
+    #[pack(crate = "crate")]
+    #[instance(class = "MapClass", source = "clay_core/map/map.h", name = "test_map")]
+    #[instance(class = "ShapeClass", source = "clay_core/shape/shape.h")]
+    struct ScaleCube {
+        scale: f64,
+    }
+
+    #[test]
+    fn derive_instance() {
+        assert_eq!(<ScaleCube as Instance<MapClass>>::inst_name(), "test_map");
+        assert_eq!(<ScaleCube as Instance<ShapeClass>>::inst_name(), "scale_cube");
+        let source = <ScaleCube as Instance<ShapeClass>>::source(&mut HashSet::new());
+        assert!(source.contains("#define SCALE_CUBE_LAYOUT "));
+        assert!(source.ends_with("#include <clay_core/shape/shape.h>"));
+    }
+}
@@ -10,12 +10,26 @@ pub struct Context {
 
 impl Context {
     pub fn new(platform: ocl::Platform, device: ocl::Device) -> crate::Result<Self> {
+        Self::create(platform, device, None)
+    }
+
+    /// Creates context with profiling enabled on its queue,
+    /// so that the kernel execution times could be measured.
+    pub fn with_profiling(platform: ocl::Platform, device: ocl::Device) -> crate::Result<Self> {
+        Self::create(platform, device, Some(ocl::flags::QUEUE_PROFILING_ENABLE))
+    }
+
+    fn create(
+        platform: ocl::Platform,
+        device: ocl::Device,
+        properties: Option<ocl::flags::CommandQueueProperties>,
+    ) -> crate::Result<Self> {
         let context = ocl::Context::builder()
         .platform(platform)
         .devices(device.clone())
         .build()?;
 
-        let queue = ocl::Queue::new(&context, device, None)?;
+        let queue = ocl::Queue::new(&context, device, properties)?;
 
         Ok(Self { platform, device, context, queue })
     }
@@ -33,3 +47,45 @@ impl Context {
         &self.queue
     }
 }
+
+/// Context on the first available device for the tests that need OpenCL.
+///
+/// Returns `None` if there is no OpenCL platform or device, so that such tests are skipped.
+#[cfg(test)]
+pub(crate) fn test_context() -> Option<Context> {
+    let platform = ocl::Platform::new(*ocl::core::get_platform_ids().ok()?.first()?);
+    let device = ocl::Device::first(platform).ok()?;
+    Context::new(platform, device).ok()
+}
+
+/// Set of contexts for several devices, possibly on different platforms.
+pub struct MultiContext {
+    contexts: Vec<Context>,
+}
+
+impl MultiContext {
+    pub fn new(devices: &[(ocl::Platform, ocl::Device)]) -> crate::Result<Self> {
+        if devices.is_empty() {
+            return Err("device list is empty".into());
+        }
+        let contexts = devices.iter()
+        .map(|(platform, device)| Context::new(*platform, *device))
+        .collect::<crate::Result<Vec<_>>>()?;
+        Ok(Self { contexts })
+    }
+
+    /// Creates contexts for all devices of all available platforms.
+    pub fn all() -> crate::Result<Self> {
+        let mut devices = Vec::new();
+        for platform in ocl::Platform::list() {
+            for device in ocl::Device::list_all(platform)? {
+                devices.push((platform, device));
+            }
+        }
+        Self::new(&devices)
+    }
+
+    pub fn contexts(&self) -> &[Context] {
+        &self.contexts
+    }
+}
@@ -2,12 +2,15 @@ use std::io;
 use std::fmt;
 
 use ocl;
+use crate::BuildDiagnostic;
 
 
 #[derive(Debug)]
 pub enum Error {
     Io(io::Error),
     Ocl(ocl::Error),
+    /// OpenCL program build failure.
+    Build(Vec<BuildDiagnostic>),
     Other(String),
 }
 
@@ -16,6 +19,13 @@ impl fmt::Display for Error {
         match self {
             Error::Io(e) => write!(f, "Io: {:?}\n{}", e.kind(), e),
             Error::Ocl(e) => write!(f, "Ocl:\n{}", e),
+            Error::Build(ds) => {
+                write!(f, "Build:")?;
+                for d in ds.iter() {
+                    write!(f, "\n{}", d)?;
+                }
+                Ok(())
+            },
             Error::Other(s) => write!(f, "Other:\n{}", s),
         }
     }
@@ -33,6 +43,12 @@ impl From<ocl::Error> for Error {
     }
 }
 
+impl From<ocl::OclCoreError> for Error {
+    fn from(e: ocl::OclCoreError) -> Self {
+        Error::Ocl(e.into())
+    }
+}
+
 impl From<String> for Error {
     fn from(s: String) -> Self {
         Error::Other(s)
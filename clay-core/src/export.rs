@@ -0,0 +1,80 @@
+use std::{
+    fs::File,
+    io::{Write, BufWriter},
+    path::Path,
+};
+
+
+/// Writes the image in PFM (portable float map) format.
+///
+/// `data` contains rows from top to bottom with `channels` (1 or 3) floats per pixel.
+pub fn write_pfm<W: Write>(
+    writer: &mut W,
+    dims: (usize, usize),
+    channels: usize,
+    data: &[f32],
+) -> crate::Result<()> {
+    let magic = match channels {
+        1 => "Pf",
+        3 => "PF",
+        _ => return Err("PFM supports only 1 or 3 channels".into()),
+    };
+    if data.len() != channels*dims.0*dims.1 {
+        return Err("image data size mismatch".into());
+    }
+    // Negative scale means little-endian data
+    write!(writer, "{}\n{} {}\n-1.0\n", magic, dims.0, dims.1)?;
+    let row = channels*dims.0;
+    // PFM rows go from bottom to top
+    for y in (0..dims.1).rev() {
+        for x in data[(y*row)..((y + 1)*row)].iter() {
+            writer.write_all(&x.to_le_bytes())?;
+        }
+    }
+    Ok(())
+}
+
+/// Saves the image to PFM file.
+pub fn save_pfm<P: AsRef<Path>>(
+    path: P,
+    dims: (usize, usize),
+    channels: usize,
+    data: &[f32],
+) -> crate::Result<()> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    write_pfm(&mut writer, dims, channels, data)?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// Writes RGB bytes in binary PPM format.
+pub fn write_ppm<W: Write>(writer: &mut W, dims: (usize, usize), data: &[u8]) -> crate::Result<()> {
+    if data.len() != 3*dims.0*dims.1 {
+        return Err("image data size mismatch".into());
+    }
+    write!(writer, "P6\n{} {}\n255\n", dims.0, dims.1)?;
+    writer.write_all(data)?;
+    Ok(())
+}
+
+/// Saves RGB bytes to PPM file.
+pub fn save_ppm<P: AsRef<Path>>(path: P, dims: (usize, usize), data: &[u8]) -> crate::Result<()> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    write_ppm(&mut writer, dims, data)?;
+    writer.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn pfm() {
+        let mut buffer = Vec::new();
+        write_pfm(&mut buffer, (1, 2), 1, &[1.0, 2.0]).unwrap();
+        let header = b"Pf\n1 2\n-1.0\n";
+        assert_eq!(&buffer[..header.len()], header);
+        assert_eq!(&buffer[header.len()..], [2f32.to_le_bytes(), 1f32.to_le_bytes()].concat().as_slice());
+    }
+}
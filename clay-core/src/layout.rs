@@ -0,0 +1,305 @@
+use crate::{Pack, TypeHash};
+
+
+/// Description of the packed data layout for OpenCL code.
+#[derive(Clone, Debug)]
+pub enum Layout {
+    /// Value of some built-in OpenCL type.
+    Primitive {
+        /// Type name in OpenCL (e.g. `float3`).
+        cl_type: String,
+        /// Expression that loads the value.
+        ///
+        /// The `{ibuf}` and `{fbuf}` placeholders are replaced
+        /// with int and float buffer pointers respectively.
+        load: String,
+    },
+    /// Structure of named fields.
+    Struct {
+        /// Type name of the structure in OpenCL (e.g. `Linear`).
+        name: String,
+        /// Prefix for the generated functions and macros (e.g. `linear`).
+        prefix: String,
+        size_int: usize,
+        size_float: usize,
+        fields: Vec<Field>,
+        /// Hash of the Rust type to detect different types using the same name.
+        type_hash: u64,
+    },
+}
+
+/// Named field of a structure.
+#[derive(Clone, Debug)]
+pub struct Field {
+    pub name: String,
+    pub offset_int: usize,
+    pub offset_float: usize,
+    /// Layout of the field type if it is described.
+    pub layout: Option<Layout>,
+}
+
+impl Field {
+    /// Creates a field of type `T` placed at specified offsets.
+    pub fn new<T: Pack>(name: &str, offset_int: usize, offset_float: usize) -> Self {
+        Self {
+            name: name.to_string(),
+            offset_int, offset_float,
+            layout: T::layout(),
+        }
+    }
+}
+
+/// Builder of structure fields placed one after another.
+///
+/// Offsets are computed from the sizes of the preceding fields,
+/// so they match the data written by the sequential `Packer`.
+#[derive(Clone, Debug, Default)]
+pub struct FieldsBuilder {
+    fields: Vec<Field>,
+    offset_int: usize,
+    offset_float: usize,
+}
+
+impl FieldsBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the field of type `T`.
+    pub fn field<T: Pack>(self, name: &str) -> Self {
+        self.field_in::<T, T>(name)
+    }
+
+    /// Adds the field of type `T` that is stored as the data of `S`,
+    /// e.g. the value of the single-field wrapper `S`.
+    pub fn field_in<S: Pack, T: Pack>(mut self, name: &str) -> Self {
+        self.fields.push(Field::new::<T>(name, self.offset_int, self.offset_float));
+        self.skip::<S>()
+    }
+
+    /// Skips the data of type `T` that is not described.
+    pub fn skip<T: Pack>(mut self) -> Self {
+        self.offset_int += T::size_int();
+        self.offset_float += T::size_float();
+        self
+    }
+
+    pub fn build(self) -> Vec<Field> {
+        self.fields
+    }
+}
+
+impl Layout {
+    /// Creates a primitive layout.
+    pub fn primitive(cl_type: &str, load: &str) -> Self {
+        Layout::Primitive { cl_type: cl_type.to_string(), load: load.to_string() }
+    }
+
+    /// Creates a structure layout of type `T`.
+    ///
+    /// The `name` and `prefix` must be unique for the type within a program.
+    pub fn structure<T: Pack + 'static>(name: &str, prefix: &str, fields: Vec<Field>) -> Self {
+        Layout::Struct {
+            name: name.to_string(),
+            prefix: prefix.to_string(),
+            size_int: T::size_int(),
+            size_float: T::size_float(),
+            fields,
+            type_hash: T::type_hash(),
+        }
+    }
+
+    /// Type name in OpenCL.
+    ///
+    /// Structures without described fields have no OpenCL type.
+    pub fn cl_type(&self) -> Option<String> {
+        match self {
+            Layout::Primitive { cl_type, .. } => Some(cl_type.clone()),
+            Layout::Struct { name, .. } => if self.members().next().is_some() {
+                Some(name.clone())
+            } else {
+                None
+            },
+        }
+    }
+
+    /// OpenCL expression that loads the value from `ibuf` and `fbuf` pointers.
+    pub fn cl_load(&self, ibuf: &str, fbuf: &str) -> String {
+        match self {
+            Layout::Primitive { load, .. } => {
+                load
+                .replace("{ibuf}", &format!("({})", ibuf))
+                .replace("{fbuf}", &format!("({})", fbuf))
+            },
+            Layout::Struct { prefix, .. } => format!("{}_load({}, {})", prefix, ibuf, fbuf),
+        }
+    }
+
+    /// Fields that have OpenCL type and could be stored in a structure.
+    fn members(&self) -> impl Iterator<Item=(&Field, String)> {
+        let fields: &[Field] = match self {
+            Layout::Primitive { .. } => &[],
+            Layout::Struct { fields, .. } => fields,
+        };
+        fields.iter().filter_map(|f| {
+            f.layout.as_ref().and_then(|l| l.cl_type()).map(|t| (f, t))
+        })
+    }
+
+    /// OpenCL definitions of the structure, its loading function and offset macros.
+    ///
+    /// The definitions are guarded so the source could be safely included several times.
+    /// If another type has already defined the layout with the same prefix,
+    /// the source produces a compilation error. Primitive layouts produce no code.
+    pub fn source(&self) -> String {
+        let (name, prefix, size_int, size_float, fields, type_hash) = match self {
+            Layout::Primitive { .. } => return String::new(),
+            Layout::Struct { name, prefix, size_int, size_float, fields, type_hash } => {
+                (name, prefix, size_int, size_float, fields, type_hash)
+            },
+        };
+        let pref = prefix.to_uppercase();
+        let guard = format!("{}_LAYOUT", pref);
+        let hash = format!("0x{:x}ULL", type_hash);
+
+        let mut lines = vec![
+            format!("#if defined({}) && {} != {}", guard, guard, hash),
+            format!("#error \"layout `{}` is defined by different types\"", name),
+            "#endif".to_string(),
+            format!("#ifndef {}", guard),
+            format!("#define {} {}", guard, hash),
+        ];
+        for field in fields.iter() {
+            if let Some(layout) = &field.layout {
+                lines.push(layout.source());
+            }
+        }
+        lines.push(format!("#define {}_SIZE_INT {}", pref, size_int));
+        lines.push(format!("#define {}_SIZE_FLOAT {}", pref, size_float));
+        for field in fields.iter() {
+            let fpref = format!("{}_{}", pref, field.name.to_uppercase());
+            lines.push(format!("#define {}_DI {}", fpref, field.offset_int));
+            lines.push(format!("#define {}_DF {}", fpref, field.offset_float));
+        }
+
+        let members = self.members().collect::<Vec<_>>();
+        if !members.is_empty() {
+            lines.push("#include <clay_core/matrix.h>".to_string());
+            lines.push("typedef struct {".to_string());
+            for (field, cl_type) in members.iter() {
+                lines.push(format!("\t{} {};", cl_type, field.name));
+            }
+            lines.push(format!("}} {};", name));
+
+            lines.push(format!(
+                "{} {}_load(__global const int *ibuf, __global const float *fbuf) {{",
+                name, prefix,
+            ));
+            lines.push(format!("\t{} r = {{", name));
+            let inits = members.iter().map(|(field, _)| {
+                let fpref = format!("{}_{}", pref, field.name.to_uppercase());
+                format!(
+                    "\t\t.{} = {}",
+                    field.name,
+                    field.layout.as_ref().unwrap().cl_load(
+                        &format!("ibuf + {}_DI", fpref),
+                        &format!("fbuf + {}_DF", fpref),
+                    ),
+                )
+            }).collect::<Vec<_>>();
+            lines.push(inits.join(",\n"));
+            lines.push("\t};".to_string());
+            lines.push("\treturn r;".to_string());
+            lines.push("}".to_string());
+        }
+
+        lines.push(format!("#endif // {}", guard));
+        lines.join("\n")
+    }
+}
+
+/// OpenCL definitions for the layout of `T` if it is described.
+pub fn layout_source<T: Pack>() -> String {
+    T::layout().map(|l| l.source()).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod test {
+    use nalgebra::{Vector3, Matrix3};
+    use crate::pack::*;
+    use super::*;
+
+    struct TestLayout;
+
+    impl Pack for TestLayout {
+        fn size_int() -> usize { 1 }
+        fn size_float() -> usize { 12 }
+        fn pack_to(&self, _buffer_int: &mut [i32], _buffer_float: &mut [f32]) {}
+        fn layout() -> Option<Layout> {
+            Some(Layout::structure::<Self>("Test", "test", FieldsBuilder::new()
+                .field::<i32>("index")
+                .field::<Vector3<f64>>("pos")
+                .field::<Matrix3<f64>>("ori")
+                .build()
+            ))
+        }
+    }
+
+    #[test]
+    fn source() {
+        let source = layout_source::<TestLayout>();
+        assert!(source.contains("#define TEST_SIZE_FLOAT 12"));
+        assert!(source.contains("#define TEST_ORI_DF 3"));
+        assert!(source.contains("\tfloat3 pos;"));
+        assert!(source.contains(".pos = vload3(0, (fbuf + TEST_POS_DF))"));
+        assert!(source.contains(".ori = matrix3_load((fbuf + TEST_ORI_DF))"));
+    }
+
+    #[allow(dead_code)]
+    #[derive(Pack)]
+    #[pack(crate = "crate")]
+    struct Wrapper<T: Pack> {
+        value: T,
+    }
+
+    #[test]
+    fn generic_names() {
+        let (a, b) = (Wrapper::<f64>::layout().unwrap(), Wrapper::<i32>::layout().unwrap());
+        let (ta, tb) = (a.cl_type().unwrap(), b.cl_type().unwrap());
+        assert!(ta.starts_with("Wrapper_"));
+        assert_ne!(ta, tb);
+        assert_ne!(a.cl_load("i", "f"), b.cl_load("i", "f"));
+        assert_eq!(Wrapper::<f64>::layout().unwrap().cl_type().unwrap(), ta);
+    }
+
+    #[allow(dead_code)]
+    mod other {
+        use crate::pack::*;
+        #[derive(Pack)]
+        #[pack(crate = "crate")]
+        pub struct Wrapper {
+            pub value: f64,
+        }
+        #[derive(Pack)]
+        #[pack(crate = "crate")]
+        pub struct Test {
+            pub value: f64,
+        }
+    }
+
+    #[test]
+    fn name_collision() {
+        let (a, b) = (layout_source::<TestLayout>(), layout_source::<other::Test>());
+        // Both define `TEST_LAYOUT` guard with different hashes,
+        // so the second one fails to compile after the first one
+        let guard = |s: &str| s.lines().find(|l| l.starts_with("#define TEST_LAYOUT ")).unwrap().to_string();
+        assert_ne!(guard(&a), guard(&b));
+        let hash_b = guard(&b).split_whitespace().last().unwrap().to_string();
+        assert!(b.contains(&format!("#if defined(TEST_LAYOUT) && TEST_LAYOUT != {}", hash_b)));
+        assert!(b.contains("#error"));
+        assert_eq!(guard(&a), guard(&layout_source::<TestLayout>()));
+
+        // Non-generic type is not renamed
+        assert_eq!(other::Wrapper::layout().unwrap().cl_type().unwrap(), "Wrapper");
+    }
+}
@@ -7,12 +7,17 @@ pub mod push;
 pub use push::*;
 pub mod pack;
 pub use pack::*;
+pub mod layout;
+pub use layout::*;
 pub mod type_hash;
 pub use type_hash::*;
 pub mod class;
 pub use class::*;
 pub mod select;
 
+pub mod space;
+pub use space::*;
+
 pub mod map;
 pub use map::*;
 pub mod shape;
@@ -37,3 +42,12 @@ pub mod worker;
 pub use worker::*;
 pub mod buffer;
 pub use buffer::*;
+pub mod stats;
+pub use stats::*;
+pub mod aov;
+pub use aov::*;
+pub mod export;
+pub mod pick;
+pub use pick::*;
+pub mod animation;
+pub use animation::*;
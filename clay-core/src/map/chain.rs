@@ -9,7 +9,7 @@ pub struct Chain<F: Map, S: Map> {
 
 impl<F: Map, S: Map> Chain<F, S> {
     pub fn new(first: F, second: S) -> Self {
-        Self { first, second }
+        Ok(Self { first, second })
     }
 }
 
@@ -57,3 +57,12 @@ impl<F: Map, S: Map> Pack for Chain<F, S> {
         .pack(&self.second);
     }
 }
+
+impl<F: Map + Unpack, S: Map + Unpack> Unpack for Chain<F, S> {
+    fn unpack_from(buffer_int: &[i32], buffer_float: &[f32]) -> crate::Result<Self> {
+        let mut unpacker = Unpacker::new(buffer_int, buffer_float);
+        let first = unpacker.unpack()?;
+        let second = unpacker.unpack()?;
+        Ok(Self { first, second })
+    }
+}
@@ -5,10 +5,12 @@ use crate::{
     class::*,
     material::*,
     TypeHash,
+    Editable, Edit,
 };
 
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Pack, Unpack)]
+#[pack(crate = "crate")]
 pub struct Colored<M: Material> {
     pub material: M,
     pub color: Vector3<f64>,
@@ -53,12 +55,14 @@ impl<M: Material> Instance<MaterialClass> for Colored<M> {
     }
 }
 
-impl<M: Material> Pack for Colored<M> {
-    fn size_int() -> usize { M::size_int() + 0 }
-    fn size_float() -> usize { M::size_float() + 3 }
-
-    fn pack_to(&self, buffer_int: &mut [i32], buffer_float: &mut [f32]) {
-        self.material.pack_to(buffer_int, buffer_float);
-        self.color.pack_float_to(&mut buffer_float[M::size_float()..]);
+impl<M: Material> Editable for Colored<M> {
+    fn edit(&mut self, edit: &Edit) -> bool {
+        match edit {
+            Edit::Recolor(delta) => {
+                self.color = (self.color + delta).map(|x| x.clamp(0.0, 1.0));
+                true
+            },
+            _ => false,
+        }
     }
 }
@@ -117,6 +117,16 @@ macro_rules! material_combine {
                 )+;
             }
         }
+
+        impl $crate::Unpack for $Combine {
+            fn unpack_from(buffer_int: &[i32], buffer_float: &[f32]) -> $crate::Result<Self> {
+                use $crate::pack::*;
+                let mut unpacker = Unpacker::new(buffer_int, buffer_float);
+                Ok(Self {
+                    $( $field: (unpacker.unpack()?, unpacker.unpack()?), )+
+                })
+            }
+        }
     };
 }
 
@@ -1,9 +1,11 @@
 use std::collections::HashSet;
+use nalgebra::Vector3;
 use crate::{
-    Pack, Packer,
+    Pack, Packer, Unpack, Unpacker,
     TypeHash, class::*,
     shape::*, material::*,
     object::*,
+    Editable, Edit, HostRay,
 };
 
 
@@ -16,7 +18,7 @@ pub struct Covered<S: Shape, M: Material> {
 
 impl<S: Shape, M: Material> Covered<S, M> {
     pub fn new(shape: S, material: M) -> Self {
-        Self { shape, material }
+        Ok(Self { shape, material })
     }
 
     fn shape_source(cache: &mut HashSet<u64>) -> String {
@@ -84,6 +86,15 @@ impl<S: Shape, M: Material> Pack for Covered<S, M> {
     }
 }
 
+impl<S: Shape + Unpack, M: Material + Unpack> Unpack for Covered<S, M> {
+    fn unpack_from(buffer_int: &[i32], buffer_float: &[f32]) -> crate::Result<Self> {
+        let mut unpacker = Unpacker::new(buffer_int, buffer_float);
+        let shape = unpacker.unpack()?;
+        let material = unpacker.unpack()?;
+        Ok(Self { shape, material })
+    }
+}
+
 impl<B: Bound, S: Shape + Bounded<B>, M: Material> Bounded<B> for Covered<S, M> {
     fn bound(&self) -> Option<B> {
         self.shape.bound()
@@ -96,3 +107,18 @@ impl<T: Bound + Target, S: Shape + Bounded<T>, M: Material> Targeted<T> for Cove
         .map(|t| (t, self.material.brightness()))
     }
 }
+
+impl<S: Shape + Editable, M: Material + Editable> Editable for Covered<S, M> {
+    fn bounding_sphere(&self) -> Option<(Vector3<f64>, f64)> {
+        self.shape.bounding_sphere()
+    }
+    fn hit(&self, ray: &HostRay) -> Option<f64> {
+        self.shape.hit(ray)
+    }
+    fn edit(&mut self, edit: &Edit) -> bool {
+        match edit {
+            Edit::Recolor(_) => self.material.edit(edit),
+            _ => self.shape.edit(edit),
+        }
+    }
+}
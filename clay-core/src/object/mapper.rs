@@ -9,7 +9,7 @@ pub struct ObjectMapper<O: Object, M: Map> {
 
 impl<O: Object, M: Map> ObjectMapper<O, M> {
     pub fn new(object: O, map: M) -> Self {
-        Self { object, map }
+        Ok(Self { object, map })
     }
 }
 
@@ -55,3 +55,12 @@ impl<O: Object, M: Map> Pack for ObjectMapper<O, M> {
         .pack(&self.map);
     }
 }
+
+impl<O: Object + Unpack, M: Map + Unpack> Unpack for ObjectMapper<O, M> {
+    fn unpack_from(buffer_int: &[i32], buffer_float: &[f32]) -> crate::Result<Self> {
+        let mut unpacker = Unpacker::new(buffer_int, buffer_float);
+        let object = unpacker.unpack()?;
+        let map = unpacker.unpack()?;
+        Ok(Self { object, map })
+    }
+}
@@ -1,4 +1,7 @@
 use nalgebra::{Scalar, Vector3, Matrix3};
+use crate::layout::Layout;
+
+pub use clay_derive::{Pack, Unpack};
 
 
 /// Something that could be packed to `i32` buffers
@@ -35,6 +38,27 @@ pub trait Pack {
     ///
     /// Buffers *must* be of size greater or equal to object's one.
     fn pack_to(&self, buffer_int: &mut [i32], buffer_float: &mut [f32]);
+
+    /// Layout of the packed data that is used to generate OpenCL accessors.
+    ///
+    /// Returns `None` if the layout is not described.
+    fn layout() -> Option<Layout> { None }
+}
+
+/// Something that could be unpacked from a pair of `i32` and `f32` buffers.
+///
+/// This is the inverse of `Pack`: unpacking the data written by `pack_to`
+/// gives back the same object (up to the precision loss of `f64` to `f32` conversion).
+pub trait Unpack: Sized {
+    /// Read an object from int and float buffers.
+    ///
+    /// Returns an error if the buffers are shorter than the object
+    /// or contain data that could not be produced by `pack_to`.
+    fn unpack_from(buffer_int: &[i32], buffer_float: &[f32]) -> crate::Result<Self>;
+}
+
+fn too_short<T>() -> crate::Error {
+    format!("buffer is too short to unpack `{}`", std::any::type_name::<T>()).into()
 }
 
 pub trait PackerInt {
@@ -74,6 +98,29 @@ impl<'a> Packer<'a> {
     }
 }
 
+pub struct Unpacker<'a> {
+    buffer_int: &'a [i32],
+    buffer_float: &'a [f32],
+}
+impl<'a> Unpacker<'a> {
+    pub fn new(buffer_int: &'a [i32], buffer_float: &'a [f32]) -> Self {
+        Self { buffer_int, buffer_float }
+    }
+    pub fn unpack<T: Pack + Unpack>(&mut self) -> crate::Result<T> {
+        let (buffer_int, buffer_float) = match (
+            self.buffer_int.get(T::size_int()..),
+            self.buffer_float.get(T::size_float()..),
+        ) {
+            (Some(bi), Some(bf)) => (bi, bf),
+            _ => return Err(too_short::<T>()),
+        };
+        let t = T::unpack_from(self.buffer_int, self.buffer_float)?;
+        self.buffer_int = buffer_int;
+        self.buffer_float = buffer_float;
+        Ok(t)
+    }
+}
+
 
 impl PackInt for i32 {
     fn size() -> usize { 1 }
@@ -87,6 +134,14 @@ impl Pack for i32 {
     fn pack_to(&self, buffer_int: &mut [i32], _buffer_float: &mut [f32]) {
         buffer_int[0] = *self;
     }
+    fn layout() -> Option<Layout> {
+        Some(Layout::primitive("int", "{ibuf}[0]"))
+    }
+}
+impl Unpack for i32 {
+    fn unpack_from(buffer_int: &[i32], _buffer_float: &[f32]) -> crate::Result<Self> {
+        buffer_int.first().copied().ok_or_else(too_short::<Self>)
+    }
 }
 impl PackInt for u32 {
     fn size() -> usize { 1 }
@@ -100,6 +155,14 @@ impl Pack for u32 {
     fn pack_to(&self, buffer_int: &mut [i32], _buffer_float: &mut [f32]) {
         buffer_int[0] = *self as i32;
     }
+    fn layout() -> Option<Layout> {
+        Some(Layout::primitive("int", "{ibuf}[0]"))
+    }
+}
+impl Unpack for u32 {
+    fn unpack_from(buffer_int: &[i32], _buffer_float: &[f32]) -> crate::Result<Self> {
+        buffer_int.first().map(|x| *x as u32).ok_or_else(too_short::<Self>)
+    }
 }
 
 impl PackFloat for f32 {
@@ -114,6 +177,14 @@ impl Pack for f32 {
     fn pack_to(&self, _buffer_int: &mut [i32], buffer_float: &mut [f32]) {
         buffer_float[0] = *self;
     }
+    fn layout() -> Option<Layout> {
+        Some(Layout::primitive("float", "{fbuf}[0]"))
+    }
+}
+impl Unpack for f32 {
+    fn unpack_from(_buffer_int: &[i32], buffer_float: &[f32]) -> crate::Result<Self> {
+        buffer_float.first().copied().ok_or_else(too_short::<Self>)
+    }
 }
 impl PackFloat for f64 {
     fn size() -> usize { 1 }
@@ -127,6 +198,14 @@ impl Pack for f64 {
     fn pack_to(&self, _buffer_int: &mut [i32], buffer_float: &mut [f32]) {
         buffer_float[0] = *self as f32;
     }
+    fn layout() -> Option<Layout> {
+        Some(Layout::primitive("float", "{fbuf}[0]"))
+    }
+}
+impl Unpack for f64 {
+    fn unpack_from(_buffer_int: &[i32], buffer_float: &[f32]) -> crate::Result<Self> {
+        buffer_float.first().map(|x| *x as f64).ok_or_else(too_short::<Self>)
+    }
 }
 
 impl<T: PackFloat + Scalar> PackFloat for Vector3<T> {
@@ -146,6 +225,21 @@ impl<T: Pack + Scalar> Pack for Vector3<T> {
             packer = packer.pack(x);
         }
     }
+    fn layout() -> Option<Layout> {
+        match T::layout()?.cl_type()?.as_str() {
+            "int" => Some(Layout::primitive("int3", "vload3(0, {ibuf})")),
+            "float" => Some(Layout::primitive("float3", "vload3(0, {fbuf})")),
+            _ => None,
+        }
+    }
+}
+
+impl<T: Pack + Unpack + Scalar> Unpack for Vector3<T> {
+    fn unpack_from(buffer_int: &[i32], buffer_float: &[f32]) -> crate::Result<Self> {
+        let mut unpacker = Unpacker::new(buffer_int, buffer_float);
+        let xs = (0..3).map(|_| unpacker.unpack::<T>()).collect::<crate::Result<Vec<_>>>()?;
+        Ok(Self::from_iterator(xs))
+    }
 }
 
 
@@ -166,4 +260,108 @@ impl<T: Pack + Scalar> Pack for Matrix3<T> {
             packer = packer.pack(x);
         }
     }
+    fn layout() -> Option<Layout> {
+        match T::layout()?.cl_type()?.as_str() {
+            "float" => Some(Layout::primitive("matrix3", "matrix3_load({fbuf})")),
+            _ => None,
+        }
+    }
+}
+impl<T: Pack + Unpack + Scalar> Unpack for Matrix3<T> {
+    fn unpack_from(buffer_int: &[i32], buffer_float: &[f32]) -> crate::Result<Self> {
+        let mut unpacker = Unpacker::new(buffer_int, buffer_float);
+        let xs = (0..9).map(|_| unpacker.unpack::<T>()).collect::<crate::Result<Vec<_>>>()?;
+        Ok(Self::from_iterator(xs))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use nalgebra::Vector3;
+    use crate::layout::Layout;
+    use super::*;
+
+    #[derive(Debug, PartialEq, Pack, Unpack)]
+    #[pack(crate = "crate")]
+    struct Pair {
+        #[pack(name = "offset")]
+        pos: Vector3<f64>,
+        count: i32,
+    }
+
+    #[derive(Debug, PartialEq, Pack, Unpack)]
+    #[pack(crate = "crate")]
+    struct Wrapper<T> {
+        value: T,
+        scale: f64,
+    }
+
+    #[derive(Debug, PartialEq, Pack, Unpack)]
+    #[pack(crate = "crate")]
+    struct Scale(f64);
+
+    #[derive(Debug, PartialEq, Pack, Unpack)]
+    #[pack(crate = "crate")]
+    struct Unit;
+
+    fn round_trip<T: Pack + Unpack>(object: &T) -> T {
+        let mut buffer_int = vec![0; T::size_int()];
+        let mut buffer_float = vec![0.0; T::size_float()];
+        object.pack_to(&mut buffer_int, &mut buffer_float);
+        T::unpack_from(&buffer_int, &buffer_float).unwrap()
+    }
+
+    /// Name and prefix of the structure layout.
+    fn names<T: Pack>() -> (String, String) {
+        match T::layout().unwrap() {
+            Layout::Struct { name, prefix, .. } => (name, prefix),
+            Layout::Primitive { .. } => panic!("structure layout expected"),
+        }
+    }
+
+    /// Names and offsets of the structure fields.
+    fn offsets<T: Pack>() -> Vec<(String, usize, usize)> {
+        match T::layout().unwrap() {
+            Layout::Struct { fields, .. } => {
+                fields.into_iter().map(|f| (f.name, f.offset_int, f.offset_float)).collect()
+            },
+            Layout::Primitive { .. } => panic!("structure layout expected"),
+        }
+    }
+
+    fn field(name: &str, offset_int: usize, offset_float: usize) -> (String, usize, usize) {
+        (name.to_string(), offset_int, offset_float)
+    }
+
+    #[test]
+    fn derive_round_trip() {
+        let pair = Pair { pos: Vector3::new(0.5, -1.25, 2.0), count: 3 };
+        assert_eq!(round_trip(&pair), pair);
+        let wrapper = Wrapper { value: Vector3::new(1, 2, 3), scale: 0.25 };
+        assert_eq!(round_trip(&wrapper), wrapper);
+        let wrapper = Wrapper { value: Scale(-4.0), scale: 8.0 };
+        assert_eq!(round_trip(&wrapper), wrapper);
+        assert_eq!(round_trip(&Unit), Unit);
+
+        assert!(Pair::unpack_from(&[], &[0.0; 3]).is_err());
+        assert!(Pair::unpack_from(&[1], &[0.0; 2]).is_err());
+    }
+
+    #[test]
+    fn derive_layout() {
+        assert_eq!(names::<Pair>(), ("Pair".to_string(), "pair".to_string()));
+        assert_eq!((Pair::size_int(), Pair::size_float()), (1, 3));
+        assert_eq!(offsets::<Pair>(), vec![field("offset", 0, 0), field("count", 0, 3)]);
+
+        assert_eq!((Scale::size_int(), Scale::size_float()), (0, 1));
+        assert_eq!(offsets::<Scale>(), vec![field("_0", 0, 0)]);
+
+        // Generic types get the names unique for the parameters
+        let (name, prefix) = names::<Wrapper<i32>>();
+        assert!(name.starts_with("Wrapper_") && prefix.starts_with("wrapper_"));
+        assert_ne!(name, names::<Wrapper<Vector3<f64>>>().0);
+        assert_eq!((Wrapper::<i32>::size_int(), Wrapper::<i32>::size_float()), (1, 1));
+        assert_eq!(offsets::<Wrapper<i32>>(), vec![field("value", 0, 0), field("scale", 1, 0)]);
+        assert_eq!(offsets::<Wrapper<Vector3<f64>>>(), vec![field("value", 0, 0), field("scale", 0, 3)]);
+    }
 }
@@ -0,0 +1,211 @@
+use nalgebra::{Vector3, Matrix3};
+
+
+/// Ray traced on the host side.
+#[derive(Clone, Debug, PartialEq)]
+pub struct HostRay {
+    pub start: Vector3<f64>,
+    /// Normalized direction.
+    pub dir: Vector3<f64>,
+}
+
+impl HostRay {
+    pub fn new(start: Vector3<f64>, dir: Vector3<f64>) -> Self {
+        Self { start, dir: dir.normalize() }
+    }
+
+    /// Distance to the nearest intersection with the sphere in front of the ray.
+    ///
+    /// If the ray starts inside the sphere then zero is returned.
+    pub fn hit_sphere(&self, center: &Vector3<f64>, radius: f64) -> Option<f64> {
+        let rel = center - self.start;
+        let proj = rel.dot(&self.dir);
+        let d2 = rel.norm_squared() - proj*proj;
+        let r2 = radius*radius;
+        if d2 > r2 {
+            return None;
+        }
+        let half = (r2 - d2).sqrt();
+        if proj + half < 0.0 {
+            None
+        } else {
+            Some((proj - half).max(0.0))
+        }
+    }
+
+    /// Start and direction of the ray in the space of the `x -> ori*x + pos` map source,
+    /// the distance along the mapped ray is the same as along the original one.
+    fn unmap(&self, ori: &Matrix3<f64>, pos: &Vector3<f64>) -> Option<(Vector3<f64>, Vector3<f64>)> {
+        let inv = ori.try_inverse()?;
+        Some((inv*(self.start - pos), inv*self.dir))
+    }
+
+    /// Distance to the nearest intersection with the unit sphere mapped by `x -> ori*x + pos`.
+    ///
+    /// If the ray starts inside the ellipsoid then zero is returned.
+    pub fn hit_ellipsoid(&self, ori: &Matrix3<f64>, pos: &Vector3<f64>) -> Option<f64> {
+        let (start, dir) = self.unmap(ori, pos)?;
+        let a = dir.norm_squared();
+        let b = start.dot(&dir);
+        let d = b*b - a*(start.norm_squared() - 1.0);
+        if d < 0.0 {
+            return None;
+        }
+        let far = (-b + d.sqrt())/a;
+        if far < 0.0 {
+            None
+        } else {
+            Some(((-b - d.sqrt())/a).max(0.0))
+        }
+    }
+
+    /// Distance to the nearest intersection with the unit cube (of edge length two)
+    /// mapped by `x -> ori*x + pos`.
+    ///
+    /// If the ray starts inside the parallelepiped then zero is returned.
+    pub fn hit_parallelepiped(&self, ori: &Matrix3<f64>, pos: &Vector3<f64>) -> Option<f64> {
+        let (start, dir) = self.unmap(ori, pos)?;
+        let (mut near, mut far) = (std::f64::NEG_INFINITY, std::f64::INFINITY);
+        for i in 0..3 {
+            if dir[i] == 0.0 {
+                if start[i].abs() > 1.0 {
+                    return None;
+                }
+                continue;
+            }
+            let (t0, t1) = ((-1.0 - start[i])/dir[i], (1.0 - start[i])/dir[i]);
+            near = near.max(t0.min(t1));
+            far = far.min(t0.max(t1));
+        }
+        if near > far || far < 0.0 {
+            None
+        } else {
+            Some(near.max(0.0))
+        }
+    }
+}
+
+/// Change of an object made in an interactive editor.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Edit {
+    /// Moves the object by the vector.
+    Translate(Vector3<f64>),
+    /// Scales the object around its center by the factor.
+    Scale(f64),
+    /// Adds the vector to the color, the components are clamped to `[0, 1]`.
+    Recolor(Vector3<f64>),
+}
+
+/// Something that could be picked by the host-side ray and edited.
+pub trait Editable {
+    /// Center and radius of the sphere containing the whole object,
+    /// `None` if the object is unbounded or could not be picked.
+    fn bounding_sphere(&self) -> Option<(Vector3<f64>, f64)> { None }
+
+    /// Distance to the nearest intersection of the ray with the object.
+    ///
+    /// The bounding sphere is hit by default, shapes should override it with the exact intersection.
+    fn hit(&self, ray: &HostRay) -> Option<f64> {
+        let (center, radius) = self.bounding_sphere()?;
+        ray.hit_sphere(&center, radius)
+    }
+
+    /// Applies the edit, returns `false` if the edit is not supported.
+    fn edit(&mut self, _edit: &Edit) -> bool { false }
+}
+
+/// Index of the object hit first by the ray.
+///
+/// Only the objects which bounding sphere is hit are checked for the exact intersection.
+pub fn pick<'a, T: Editable + 'a, I: IntoIterator<Item=&'a T>>(objects: I, ray: &HostRay) -> Option<usize> {
+    objects.into_iter().enumerate()
+    .filter_map(|(i, obj)| {
+        let (center, radius) = obj.bounding_sphere()?;
+        ray.hit_sphere(&center, radius)?;
+        obj.hit(ray).map(|dist| (i, dist))
+    })
+    .fold(None, |best: Option<(usize, f64)>, (i, dist)| match best {
+        Some((_, bd)) if bd <= dist => best,
+        _ => Some((i, dist)),
+    })
+    .map(|(i, _)| i)
+}
+
+/// View that could emit rays on the host side.
+pub trait PickView {
+    /// Ray through the center of the pixel of the screen of `dims` size.
+    fn pick_ray(&self, pixel: (usize, usize), dims: (usize, usize)) -> HostRay;
+}
+
+/// Scene which objects could be picked and edited.
+pub trait EditableScene {
+    /// Index of the object hit first by the ray.
+    fn pick(&self, ray: &HostRay) -> Option<usize>;
+
+    /// Applies the edit to the object and writes it to the device.
+    ///
+    /// Returns `false` if the object doesn't support the edit.
+    fn edit(&mut self, index: usize, edit: &Edit) -> crate::Result<bool>;
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    struct Ball(Vector3<f64>, f64);
+    impl Editable for Ball {
+        fn bounding_sphere(&self) -> Option<(Vector3<f64>, f64)> {
+            Some((self.0, self.1))
+        }
+    }
+
+    #[test]
+    fn nearest() {
+        let ray = HostRay::new(Vector3::zeros(), Vector3::new(0.0, 2.0, 0.0));
+        let balls = [
+            Ball(Vector3::new(0.0, 5.0, 0.0), 1.0),
+            Ball(Vector3::new(0.0, 3.0, 0.0), 1.0),
+            Ball(Vector3::new(0.0, -3.0, 0.0), 1.0),
+            Ball(Vector3::new(3.0, 2.0, 0.0), 1.0),
+        ];
+        assert_eq!(pick(&balls, &ray), Some(1));
+        assert_eq!(ray.hit_sphere(&balls[1].0, 1.0), Some(2.0));
+        assert_eq!(pick(&balls[2..], &ray), None);
+    }
+
+    #[test]
+    fn ellipsoid() {
+        let ray = HostRay::new(Vector3::zeros(), Vector3::new(1.0, 0.0, 0.0));
+        let ori = Matrix3::from_diagonal(&Vector3::new(2.0, 0.5, 0.5));
+        let hit = |x, y| ray.hit_ellipsoid(&ori, &Vector3::new(x, y, 0.0));
+        assert_eq!(hit(5.0, 0.0), Some(3.0));
+        assert_eq!(hit(1.0, 0.0), Some(0.0));
+        assert_eq!(hit(-3.0, 0.0), None);
+        // The bounding sphere of radius 2 is hit, but the ellipsoid is not
+        assert_eq!(hit(5.0, 1.0), None);
+        assert_eq!(ray.hit_ellipsoid(&Matrix3::zeros(), &Vector3::zeros()), None);
+    }
+
+    #[test]
+    fn parallelepiped() {
+        let ray = HostRay::new(Vector3::zeros(), Vector3::new(1.0, 0.0, 0.0));
+        let ori = Matrix3::from_diagonal(&Vector3::new(1.0, 2.0, 0.25));
+        let hit = |x, y, z| ray.hit_parallelepiped(&ori, &Vector3::new(x, y, z));
+        assert_eq!(hit(4.0, 0.0, 0.0), Some(3.0));
+        assert_eq!(hit(4.0, 1.5, 0.0), Some(3.0));
+        assert_eq!(hit(0.5, 0.0, 0.0), Some(0.0));
+        assert_eq!(hit(-4.0, 0.0, 0.0), None);
+        // The bounding sphere is hit, but the thin box is not
+        assert_eq!(hit(4.0, 0.0, 0.5), None);
+
+        // Rotated cube is hit by its edge
+        let diag = HostRay::new(Vector3::new(-4.0, 0.0, 0.0), Vector3::new(1.0, 0.0, 0.0));
+        let rot = Matrix3::new(
+            1.0, -1.0, 0.0,
+            1.0, 1.0, 0.0,
+            0.0, 0.0, 1.0,
+        )*0.5f64.sqrt();
+        let dist = diag.hit_parallelepiped(&rot, &Vector3::zeros()).unwrap();
+        assert!((dist - (4.0 - 2.0f64.sqrt())).abs() < 1e-12);
+    }
+}
@@ -107,6 +107,31 @@ macro_rules! instance_select {
             }
         }
 
+        impl<
+            $( $Param:
+                $crate::Pack +
+                $crate::Unpack +
+                $crate::Instance<$Class> +
+                $Base
+            ),+
+        > $crate::Unpack for $Select<
+            $( $Param ),+
+        > {
+            #[allow(unused_assignments)]
+            fn unpack_from(buffer_int: &[i32], buffer_float: &[f32]) -> $crate::Result<Self> {
+                let index = <u32 as $crate::Unpack>::unpack_from(buffer_int, buffer_float)?;
+                let buffer_int = &buffer_int[1..];
+                let mut i = 0;
+                $(
+                    if index == i {
+                        return $Param::unpack_from(buffer_int, buffer_float).map($Select::$Enum);
+                    }
+                    i += 1;
+                )+
+                Err(format!("bad variant index {} of `{}`", index, stringify!($Select)).into())
+            }
+        }
+
         $(
             impl From<$Instance> for $Select {
                 fn from(origin: $Instance) -> Self {
@@ -120,6 +145,7 @@ macro_rules! instance_select {
 #[cfg(test)]
 mod check {
     use crate::{
+        pack::*,
         shape::{
             Shape, ShapeClass,
             test::TestShape,
@@ -133,4 +159,23 @@ mod check {
             Shape2(T2 = TestShape<f32>),
         }
     );
+
+    #[test]
+    fn unpack() {
+        let mut ibuf = vec![0; TestSelect::size_int()];
+        let mut fbuf = vec![0.0; TestSelect::size_float()];
+        TestSelect::from(TestShape::<f32>::new()).pack_to(&mut ibuf, &mut fbuf);
+        let select: TestSelect = Unpack::unpack_from(&ibuf, &fbuf).unwrap();
+        assert!(matches!(select, TestSelect::Shape2(_)), "wrong variant is unpacked");
+    }
+
+    #[test]
+    fn unpack_bad() {
+        let fbuf = vec![0.0; TestSelect::size_float()];
+        let mut ibuf = vec![0; TestSelect::size_int()];
+        ibuf[0] = 2;
+        let err = <TestSelect as Unpack>::unpack_from(&ibuf, &fbuf).err().unwrap();
+        assert!(err.to_string().contains("bad variant index 2 of `TestSelect`"));
+        assert!(<TestSelect as Unpack>::unpack_from(&[], &fbuf).is_err());
+    }
 }
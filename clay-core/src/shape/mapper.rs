@@ -9,7 +9,7 @@ pub struct ShapeMapper<S: Shape, M: Map> {
 
 impl<S: Shape, M: Map> ShapeMapper<S, M> {
     pub fn new(shape: S, map: M) -> Self {
-        Self { shape, map }
+        Ok(Self { shape, map })
     }
 }
 
@@ -55,3 +55,12 @@ impl<S: Shape, M: Map> Pack for ShapeMapper<S, M> {
         .pack(&self.map);
     }
 }
+
+impl<S: Shape + Unpack, M: Map + Unpack> Unpack for ShapeMapper<S, M> {
+    fn unpack_from(buffer_int: &[i32], buffer_float: &[f32]) -> crate::Result<Self> {
+        let mut unpacker = Unpacker::new(buffer_int, buffer_float);
+        let shape = unpacker.unpack()?;
+        let map = unpacker.unpack()?;
+        Ok(Self { shape, map })
+    }
+}
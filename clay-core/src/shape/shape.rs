@@ -15,6 +15,8 @@ pub trait Shape: Pack + Instance<ShapeClass> {
     ///
     /// Most common use case is applying affine transform to some unit shape.
     /// (*see `map::Affine`*)
+    ///
+    /// In non-Euclidean spaces the map must be an isometry of the space.
     fn map<M: Map>(self, map: M) -> ShapeMapper<Self, M> {
         ShapeMapper { shape: self, map }
     }
@@ -6,10 +6,12 @@ use ocl_include::{MemHook};
 include!(concat!(env!("OUT_DIR"), "/ocl_src_list.rs"));
 
 
-pub fn source() -> MemHook {
+/// Hook with the files embedded by `clay_build::embed_ocl_src`,
+/// they are included by the paths starting with `prefix`.
+pub fn embedded_hook(prefix: &str, files: &[(&str, &str)]) -> MemHook {
     let mut hook = MemHook::new();
-    let pref = Path::new("clay_core");
-    for (name, content) in OCL_SRC_LIST.iter() {
+    let pref = Path::new(prefix);
+    for (name, content) in files.iter() {
         hook.add_file(
             &pref.join(name),
             content.to_string(),
@@ -17,3 +19,7 @@ pub fn source() -> MemHook {
     }
     hook
 }
+
+pub fn source() -> MemHook {
+    embedded_hook("clay_core", &OCL_SRC_LIST)
+}
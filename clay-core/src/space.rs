@@ -0,0 +1,46 @@
+use nalgebra::Vector3;
+use ocl_include::MemHook;
+
+
+/// Geometry of the space where rays are traced.
+///
+/// The space is selected by the scene. Its OpenCL header implements
+/// the interface of `clay_core/space/space.h` and is included before other sources,
+/// so the shapes, maps and views written with this interface are specialized for the space.
+///
+/// Points are represented by a conformal model in `R^3`, the origin of the model
+/// is a point of the space and the directions at it are the Euclidean ones.
+pub trait Space {
+    /// Path of the OpenCL header that implements the space interface.
+    fn header() -> String;
+
+    /// Source of the generated `__gen/space.h` header.
+    fn source() -> String {
+        format!("#include <{}>", Self::header())
+    }
+
+    /// Hook with the sources of the crate that implements the space,
+    /// it is added to the sources of the worker. `None` if the header is in `clay_core`.
+    fn hook() -> Option<MemHook> {
+        None
+    }
+
+    /// Point of the model at the distance of `v` length from the origin in the direction of `v`.
+    fn exp(v: &Vector3<f64>) -> Vector3<f64> {
+        *v
+    }
+
+    /// Whether the point of `R^3` represents a point of the space.
+    fn contains(_pos: &Vector3<f64>) -> bool {
+        true
+    }
+}
+
+/// Ordinary flat space.
+pub enum Euclidean {}
+
+impl Space for Euclidean {
+    fn header() -> String {
+        "clay_core/space/euclidean.h".to_string()
+    }
+}
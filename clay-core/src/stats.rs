@@ -0,0 +1,128 @@
+use std::{
+    io::Write,
+    time::Duration,
+};
+
+
+/// Rendering statistics collected by the worker.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Stats {
+    /// Number of rendered passes.
+    pub passes: usize,
+    /// Number of traced paths (pixels rendered in all passes).
+    pub paths: u64,
+    /// Number of traced ray segments.
+    pub rays: u64,
+    /// Total execution time of the `render` kernel.
+    ///
+    /// Kernel times are measured only if the context is created with profiling enabled.
+    pub render_time: Duration,
+    /// Total execution time of the `draw` kernel.
+    pub draw_time: Duration,
+    /// Host time elapsed since the collection started.
+    pub wall_time: Duration,
+}
+
+const FIELDS: [&str; 9] = [
+    "passes", "paths", "rays",
+    "render_time", "draw_time", "wall_time",
+    "avg_path_length", "passes_per_second", "rays_per_second",
+];
+
+impl Stats {
+    /// Average number of ray segments per path.
+    pub fn avg_path_length(&self) -> f64 {
+        if self.paths > 0 {
+            self.rays as f64/self.paths as f64
+        } else {
+            0.0
+        }
+    }
+
+    pub fn passes_per_second(&self) -> f64 {
+        per_second(self.passes as f64, self.wall_time)
+    }
+
+    pub fn rays_per_second(&self) -> f64 {
+        per_second(self.rays as f64, self.wall_time)
+    }
+
+    fn values(&self) -> [String; 9] {
+        [
+            self.passes.to_string(),
+            self.paths.to_string(),
+            self.rays.to_string(),
+            self.render_time.as_secs_f64().to_string(),
+            self.draw_time.as_secs_f64().to_string(),
+            self.wall_time.as_secs_f64().to_string(),
+            self.avg_path_length().to_string(),
+            self.passes_per_second().to_string(),
+            self.rays_per_second().to_string(),
+        ]
+    }
+
+    /// Header line for `csv_row`. Times are in seconds.
+    pub fn csv_header() -> String {
+        FIELDS.join(",")
+    }
+
+    pub fn csv_row(&self) -> String {
+        self.values().join(",")
+    }
+
+    /// JSON object with the same fields as CSV.
+    pub fn to_json(&self) -> String {
+        let pairs = FIELDS.iter().zip(self.values().iter())
+        .map(|(k, v)| format!("\"{}\": {}", k, v))
+        .collect::<Vec<_>>();
+        format!("{{{}}}", pairs.join(", "))
+    }
+
+    /// Writes CSV row optionally preceded by the header.
+    pub fn write_csv<W: Write>(&self, writer: &mut W, header: bool) -> crate::Result<()> {
+        if header {
+            writeln!(writer, "{}", Self::csv_header())?;
+        }
+        writeln!(writer, "{}", self.csv_row())?;
+        Ok(())
+    }
+
+    pub fn write_json<W: Write>(&self, writer: &mut W) -> crate::Result<()> {
+        writeln!(writer, "{}", self.to_json())?;
+        Ok(())
+    }
+}
+
+fn per_second(value: f64, time: Duration) -> f64 {
+    let secs = time.as_secs_f64();
+    if secs > 0.0 {
+        value/secs
+    } else {
+        0.0
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn output() {
+        let stats = Stats {
+            passes: 4, paths: 40, rays: 100,
+            wall_time: Duration::from_secs(2),
+            ..Stats::default()
+        };
+        assert_eq!(stats.avg_path_length(), 2.5);
+        assert_eq!(stats.passes_per_second(), 2.0);
+
+        let mut csv = Vec::new();
+        stats.write_csv(&mut csv, true).unwrap();
+        let csv = String::from_utf8(csv).unwrap();
+        let lines = csv.lines().collect::<Vec<_>>();
+        assert_eq!(lines[0].split(',').count(), lines[1].split(',').count());
+        assert!(lines[1].starts_with("4,40,100,"));
+
+        assert!(stats.to_json().contains("\"rays_per_second\": 50"));
+    }
+}
@@ -1,7 +1,10 @@
 use std::collections::HashSet;
-use crate::Push;
+use crate::{Push, Space};
 
 
 pub trait View: Push {
+    /// Space where the rays of the view are emitted.
+    type Space: Space;
+
     fn source(cache: &mut HashSet<u64>) -> String;
 }
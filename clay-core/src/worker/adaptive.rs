@@ -0,0 +1,85 @@
+use ocl::{self, prm};
+use crate::Screen;
+
+
+/// Settings of adaptive sampling.
+///
+/// When enabled, pixels which relative error of the mean color is below the threshold
+/// are not traced anymore and accumulate their current mean instead.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Adaptive {
+    pub enabled: bool,
+    /// Convergence threshold of the relative standard error of the pixel mean.
+    pub threshold: f32,
+    /// Number of samples taken in each pixel before its convergence is checked.
+    pub min_samples: usize,
+}
+
+impl Default for Adaptive {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            threshold: 0.01,
+            min_samples: 16,
+        }
+    }
+}
+
+/// Kernel that updates the mask of unconverged pixels.
+pub(crate) struct Sampler {
+    kernel: ocl::Kernel,
+    pub settings: Adaptive,
+}
+
+impl Sampler {
+    pub(crate) fn new(program: &ocl::Program, queue: &ocl::Queue) -> crate::Result<Self> {
+        let kernel = ocl::Kernel::builder()
+        .program(program)
+        .name("adapt")
+        .queue(queue.clone())
+        .arg(prm::Int2::zero()) // screen size
+        .arg(0i32) // passes
+        .arg(0f32) // threshold
+        .arg(0u32) // min samples
+        .arg(None::<&ocl::Buffer<f32>>) // color buffer
+        .arg(None::<&ocl::Buffer<f32>>) // squares
+        .arg(None::<&ocl::Buffer<u32>>) // sample count
+        .arg(None::<&ocl::Buffer<u8>>) // mask
+        .arg(None::<&ocl::Buffer<u32>>) // unconverged count
+        .build()?;
+        Ok(Self { kernel, settings: Adaptive::default() })
+    }
+
+    /// Enqueues the update of the screen mask in the `dims` corner of the screen.
+    ///
+    /// Only this part of the screen is rendered, e.g. a cropped tile, so the pixels outside it
+    /// are not counted. Returns `false` if adaptive sampling is disabled and the mask should not be used.
+    pub(crate) fn enqueue(&mut self, screen: &Screen, dims: (usize, usize)) -> crate::Result<bool> {
+        if !self.settings.enabled || screen.n_passes() == 0 {
+            return Ok(false);
+        }
+        screen.unconverged_buffer().cmd()
+        .offset(0)
+        .fill(0u32, None)
+        .enq()?;
+
+        let size = screen.dims();
+        let kernel = &mut self.kernel;
+        kernel.set_arg(0, prm::Int2::new(size.0 as i32, size.1 as i32))?;
+        kernel.set_arg(1, screen.n_passes() as i32)?;
+        kernel.set_arg(2, self.settings.threshold)?;
+        kernel.set_arg(3, self.settings.min_samples as u32)?;
+        kernel.set_arg(4, screen.color())?;
+        kernel.set_arg(5, screen.squares())?;
+        kernel.set_arg(6, screen.sample_count())?;
+        kernel.set_arg(7, screen.mask())?;
+        kernel.set_arg(8, screen.unconverged_buffer())?;
+        unsafe {
+            kernel
+            .cmd()
+            .global_work_size(dims)
+            .enq()?;
+        }
+        Ok(true)
+    }
+}
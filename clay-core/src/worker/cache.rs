@@ -0,0 +1,124 @@
+use std::{
+    env, fs, process,
+    path::{Path, PathBuf},
+};
+use ocl::{
+    self,
+    enums::{DeviceInfo as Di},
+};
+
+
+/// Environment variable that overrides the default cache directory.
+pub const CACHE_DIR_VAR: &str = "CLAY_CACHE_DIR";
+
+/// On-disk cache of compiled program binaries.
+///
+/// Binaries are keyed by the hash of the program source, device name and driver version,
+/// so the cache entry is never used with another source or OpenCL implementation.
+#[derive(Clone, Debug)]
+pub struct ProgramCache {
+    dir: PathBuf,
+}
+
+impl ProgramCache {
+    /// Creates cache stored in the specified directory.
+    pub fn new<P: AsRef<Path>>(dir: P) -> Self {
+        Self { dir: dir.as_ref().to_path_buf() }
+    }
+
+    /// Cache directory taken from `CLAY_CACHE_DIR` variable,
+    /// or the `clay-cache` subdirectory of the temporary directory.
+    pub fn default_dir() -> PathBuf {
+        env::var_os(CACHE_DIR_VAR)
+        .map(PathBuf::from)
+        .unwrap_or_else(|| env::temp_dir().join("clay-cache"))
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Computes the key of the program with specified source for the device.
+    pub fn key(source: &str, device: &ocl::Device) -> crate::Result<u64> {
+        let name = device.info(Di::Name)?.to_string();
+        let driver = device.info(Di::DriverVersion)?.to_string();
+        Ok(Self::hash_key(source, &name, &driver))
+    }
+
+    /// FNV-1a hash of the length-prefixed strings.
+    ///
+    /// The hash of the standard library may change between Rust releases,
+    /// so the fixed one is used to keep the keys valid after a toolchain update.
+    fn hash_key(source: &str, device_name: &str, driver_version: &str) -> u64 {
+        let mut hash = 0xcbf29ce484222325u64;
+        for s in [source, device_name, driver_version].iter() {
+            let len = (s.len() as u64).to_le_bytes();
+            for b in len.iter().chain(s.as_bytes()) {
+                hash ^= *b as u64;
+                hash = hash.wrapping_mul(0x100000001b3);
+            }
+        }
+        hash
+    }
+
+    fn path(&self, key: u64) -> PathBuf {
+        self.dir.join(format!("{:016x}.bin", key))
+    }
+
+    /// Loads the binary stored with the key if it exists.
+    pub fn load(&self, key: u64) -> Option<Vec<u8>> {
+        fs::read(self.path(key)).ok()
+    }
+
+    /// Stores the binary with the key.
+    pub fn store(&self, key: u64, binary: &[u8]) -> crate::Result<()> {
+        fs::create_dir_all(&self.dir)?;
+        // Write to a temporary file first so that concurrent readers never see partial binary,
+        // the file is unique for the process so that concurrent writers don't mix their data
+        let tmp = self.dir.join(format!("{:016x}.{}.tmp", key, process::id()));
+        fs::write(&tmp, binary)?;
+        fs::rename(&tmp, self.path(key))?;
+        Ok(())
+    }
+
+    /// Removes all cached binaries.
+    pub fn clear(&self) -> crate::Result<()> {
+        if !self.dir.exists() {
+            return Ok(());
+        }
+        for entry in fs::read_dir(&self.dir)? {
+            let path = entry?.path();
+            match path.extension().and_then(|e| e.to_str()) {
+                Some("bin") | Some("tmp") => fs::remove_file(&path)?,
+                _ => (),
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Default for ProgramCache {
+    fn default() -> Self {
+        Self::new(Self::default_dir())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn store_load_clear() {
+        let cache = ProgramCache::new(env::temp_dir().join("clay-cache-test"));
+        let key = ProgramCache::hash_key("kernel void f() {}", "device", "1.0");
+        // The key must not change between builds
+        assert_eq!(key, 0x96b3b6e4904ee8e9);
+        assert_ne!(key, ProgramCache::hash_key("kernel void f() {}device", "", "1.0"));
+        assert_ne!(key, ProgramCache::hash_key("kernel void f() {}", "device", "1.1"));
+
+        cache.store(key, &[1, 2, 3]).unwrap();
+        assert_eq!(cache.load(key), Some(vec![1, 2, 3]));
+        cache.clear().unwrap();
+        assert_eq!(cache.load(key), None);
+    }
+}
@@ -0,0 +1,160 @@
+use ocl::{self, prm};
+use crate::{Screen, Aov};
+
+
+/// Settings of the edge-avoiding à-trous wavelet denoiser.
+///
+/// The denoiser runs in the draw stage before the conversion to screen bytes
+/// and is guided by normal, depth and albedo AOVs if the screen has them.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Denoise {
+    pub enabled: bool,
+    /// Number of filter iterations, the filter footprint doubles on each one.
+    pub iterations: usize,
+    /// Color tolerance of the filter, larger values smooth more.
+    ///
+    /// The tolerance is divided by the number of passes,
+    /// so the filter fades out as the image converges.
+    pub strength: f32,
+    /// Tolerance of normal difference.
+    pub normal_sigma: f32,
+    /// Tolerance of relative depth difference.
+    pub depth_sigma: f32,
+    /// Tolerance of albedo difference.
+    pub albedo_sigma: f32,
+}
+
+impl Default for Denoise {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            iterations: 4,
+            strength: 1.0,
+            normal_sigma: 0.1,
+            depth_sigma: 1.0,
+            albedo_sigma: 0.1,
+        }
+    }
+}
+
+impl Denoise {
+    /// Filter tolerances (color, normal, depth, albedo) for the iteration.
+    pub fn sigma(&self, n_passes: usize, iteration: usize) -> [f32; 4] {
+        let color = self.strength/(n_passes.max(1) as f32)/((1 << iteration) as f32);
+        let eps = 1e-6;
+        [
+            color.max(eps),
+            self.normal_sigma.max(eps),
+            self.depth_sigma.max(eps),
+            self.albedo_sigma.max(eps),
+        ]
+    }
+}
+
+/// Denoising kernel and its intermediate buffers.
+pub(crate) struct Denoiser {
+    kernel: ocl::Kernel,
+    buffers: Vec<ocl::Buffer<f32>>,
+    pub settings: Denoise,
+}
+
+impl Denoiser {
+    pub(crate) fn new(program: &ocl::Program, queue: &ocl::Queue) -> crate::Result<Self> {
+        let kernel = ocl::Kernel::builder()
+        .program(program)
+        .name("denoise")
+        .queue(queue.clone())
+        .arg(prm::Int2::zero()) // screen size
+        .arg(0i32) // step
+        .arg(0f32) // color scale
+        .arg(0f32) // feature scale
+        .arg(prm::Float4::zero()) // sigma
+        .arg(None::<&ocl::Buffer<f32>>) // source color
+        .arg(None::<&ocl::Buffer<f32>>) // destination color
+        .arg(None::<&ocl::Buffer<f32>>) // normal
+        .arg(None::<&ocl::Buffer<f32>>) // depth
+        .arg(None::<&ocl::Buffer<f32>>) // albedo
+        .build()?;
+        Ok(Self { kernel, buffers: Vec::new(), settings: Denoise::default() })
+    }
+
+    fn reserve(&mut self, len: usize) -> crate::Result<()> {
+        if self.buffers.len() == 2 && self.buffers[0].len() == len {
+            return Ok(());
+        }
+        self.buffers = (0..2).map(|_| {
+            ocl::Buffer::<f32>::builder()
+            .queue(self.kernel.default_queue().unwrap().clone())
+            .flags(ocl::flags::MEM_READ_WRITE)
+            .len(len)
+            .build()
+        }).collect::<Result<_, _>>()?;
+        Ok(())
+    }
+
+    /// Enqueues filtering of the accumulated screen color after the `wait` event.
+    ///
+    /// Returns the buffer with averaged filtered color and the event of the last iteration,
+    /// or `None` if the denoising is disabled or there is nothing to filter.
+    pub(crate) fn enqueue(
+        &mut self,
+        screen: &Screen,
+        wait: Option<&ocl::Event>,
+    ) -> crate::Result<Option<(&ocl::Buffer<f32>, ocl::Event)>> {
+        let n_passes = screen.n_passes();
+        let iterations = self.settings.iterations;
+        if !self.settings.enabled || iterations == 0 || n_passes == 0 {
+            return Ok(None);
+        }
+        self.reserve(screen.color().len())?;
+
+        let dims = screen.dims();
+        let scale = 1.0/(n_passes as f32);
+        let kernel = &mut self.kernel;
+        kernel.set_arg(0, prm::Int2::new(dims.0 as i32, dims.1 as i32))?;
+        kernel.set_arg(3, scale)?;
+        kernel.set_arg(7, screen.aov(Aov::Normal))?;
+        kernel.set_arg(8, screen.aov(Aov::Depth))?;
+        kernel.set_arg(9, screen.aov(Aov::Albedo))?;
+
+        let mut last: Option<ocl::Event> = None;
+        for i in 0..iterations {
+            let src = if i == 0 { screen.color() } else { &self.buffers[(i + 1) % 2] };
+            let sigma = self.settings.sigma(n_passes, i);
+            kernel.set_arg(1, 1i32 << i)?;
+            kernel.set_arg(2, if i == 0 { scale } else { 1.0 })?;
+            kernel.set_arg(4, prm::Float4::new(sigma[0], sigma[1], sigma[2], sigma[3]))?;
+            kernel.set_arg(5, src)?;
+            kernel.set_arg(6, &self.buffers[i % 2])?;
+
+            let mut event = ocl::Event::empty();
+            unsafe {
+                kernel
+                .cmd()
+                .global_work_size(dims)
+                .ewait(last.as_ref().or(wait))
+                .enew(&mut event)
+                .enq()?;
+            }
+            last = Some(event);
+        }
+
+        let result = &self.buffers[(iterations - 1) % 2];
+        Ok(last.map(|event| (result, event)))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn sigma() {
+        let denoise = Denoise::default();
+        let first = denoise.sigma(1, 0);
+        assert_eq!(first[0], denoise.strength);
+        assert!(denoise.sigma(4, 0)[0] < first[0]);
+        assert!(denoise.sigma(1, 2)[0] < first[0]);
+        assert_eq!(denoise.sigma(4, 2)[1..], first[1..]);
+    }
+}
@@ -0,0 +1,163 @@
+use std::{
+    fmt,
+    path::PathBuf,
+};
+use regex::{Regex, RegexBuilder};
+use lazy_static::lazy_static;
+
+
+lazy_static!{
+    static ref DIAGNOSTIC: Regex = RegexBuilder::new(
+        r#"^(?:([^:\r\n]*):(\d+):(\d+):\s*)?(fatal error|error|warning|note):\s*(.*)$"#
+    ).build().unwrap();
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Severity {
+    Error,
+    Warning,
+    Note,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Severity::Error => write!(f, "error"),
+            Severity::Warning => write!(f, "warning"),
+            Severity::Note => write!(f, "note"),
+        }
+    }
+}
+
+/// Single message from the OpenCL compiler build log.
+///
+/// Location refers to the original source file from the include tree,
+/// line and column numbers are 1-based.
+#[derive(Clone, Debug, PartialEq)]
+pub struct BuildDiagnostic {
+    pub severity: Severity,
+    pub path: Option<PathBuf>,
+    pub line: Option<usize>,
+    pub column: Option<usize>,
+    pub message: String,
+    /// Lines of the log following the message (usually the code with the marker).
+    pub snippet: String,
+}
+
+impl BuildDiagnostic {
+    /// Diagnostic of error without location.
+    pub fn error(message: &str) -> Self {
+        Self {
+            severity: Severity::Error,
+            path: None, line: None, column: None,
+            message: message.to_string(),
+            snippet: String::new(),
+        }
+    }
+
+    /// Parses the build log.
+    ///
+    /// The `map` function converts the line number of the flattened source
+    /// into the path and line number of the original file.
+    /// Lines of the log before the first message are skipped.
+    pub fn parse<F>(log: &str, map: F) -> Vec<Self>
+    where F: Fn(usize) -> Option<(PathBuf, usize)> {
+        let mut diagnostics = Vec::<Self>::new();
+        for line in log.lines() {
+            let caps = match DIAGNOSTIC.captures(line) {
+                Some(caps) => caps,
+                None => {
+                    if let Some(last) = diagnostics.last_mut() {
+                        if !last.snippet.is_empty() {
+                            last.snippet.push('\n');
+                        }
+                        last.snippet.push_str(line);
+                    }
+                    continue;
+                },
+            };
+            let severity = match &caps[4] {
+                "warning" => Severity::Warning,
+                "note" => Severity::Note,
+                _ => Severity::Error,
+            };
+            let src_line = caps.get(2).and_then(|m| m.as_str().parse::<usize>().ok());
+            let column = caps.get(3).and_then(|m| m.as_str().parse::<usize>().ok());
+            let (path, line) = match src_line {
+                Some(l) => match map(l) {
+                    Some((path, local_line)) => (Some(path), Some(local_line)),
+                    None => (caps.get(1).map(|m| PathBuf::from(m.as_str())), Some(l)),
+                },
+                None => (None, None),
+            };
+            diagnostics.push(Self {
+                severity, path, line, column,
+                message: caps[5].to_string(),
+                snippet: String::new(),
+            });
+        }
+        diagnostics
+    }
+}
+
+impl fmt::Display for BuildDiagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(path) = &self.path {
+            write!(f, "{}:", path.to_string_lossy())?;
+        }
+        if let Some(line) = self.line {
+            write!(f, "{}:", line)?;
+        }
+        if let Some(column) = self.column {
+            write!(f, "{}:", column)?;
+        }
+        if self.path.is_some() || self.line.is_some() {
+            write!(f, " ")?;
+        }
+        write!(f, "{}: {}", self.severity, self.message)?;
+        if !self.snippet.is_empty() {
+            write!(f, "\n{}", self.snippet)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        let log = [
+            "<source>:12:5: error: use of undeclared identifier 'x'",
+            "    return x;",
+            "           ^",
+            "<source>:40:1: warning: unused variable",
+            "error: failed to build",
+        ].join("\n");
+        let diagnostics = BuildDiagnostic::parse(&log, |line| {
+            if line < 20 {
+                Some((PathBuf::from("clay/map/scale.h"), line - 10))
+            } else {
+                None
+            }
+        });
+        assert_eq!(diagnostics.len(), 3);
+
+        let d = &diagnostics[0];
+        assert_eq!(d.severity, Severity::Error);
+        assert_eq!(d.path, Some(PathBuf::from("clay/map/scale.h")));
+        assert_eq!((d.line, d.column), (Some(2), Some(5)));
+        assert_eq!(d.message, "use of undeclared identifier 'x'");
+        assert_eq!(d.snippet, "    return x;\n           ^");
+
+        let d = &diagnostics[1];
+        assert_eq!(d.severity, Severity::Warning);
+        assert_eq!(d.path, Some(PathBuf::from("<source>")));
+        assert_eq!(d.line, Some(40));
+
+        let d = &diagnostics[2];
+        assert_eq!(d.path, None);
+        assert_eq!(d.message, "failed to build");
+    }
+}
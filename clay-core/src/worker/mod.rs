@@ -1,5 +1,23 @@
+mod diagnostic;
+pub use diagnostic::*;
+
+mod cache;
+pub use cache::*;
+
+mod tile;
+pub use tile::*;
+
+mod denoise;
+pub use denoise::*;
+
+mod adaptive;
+pub use adaptive::*;
+
 mod program;
 pub use program::*;
 
 mod worker;
 pub use worker::*;
+
+mod multi_worker;
+pub use multi_worker::*;
@@ -0,0 +1,128 @@
+use std::{
+    thread,
+    time::Instant,
+    marker::PhantomData,
+};
+use crate::{MultiContext, Scene, View, buffer::MultiScreen};
+use super::{Program, Programs, Kernels, WorkerBuilder, BuildDiagnostic, Tile};
+
+
+/// Worker that renders interleaved passes on several devices simultaneously.
+///
+/// Each device renders full frame passes into its own screen of `MultiScreen`,
+/// faster devices render more passes per frame according to the measured throughput.
+pub struct MultiWorker<S: Scene, V: View<Space = S::Space>> {
+    programs: Programs<Program>,
+    diagnostics: Vec<Programs<Vec<BuildDiagnostic>>>,
+    kernels: Vec<Kernels>,
+    rates: Vec<f64>,
+    max_passes: usize,
+    phantom: PhantomData<(S, V)>,
+}
+
+impl<S: Scene, V: View<Space = S::Space>> WorkerBuilder<S, V> {
+    /// Builds the worker for all devices of the context.
+    pub fn build_multi(self, context: &MultiContext) -> crate::Result<MultiWorker<S, V>> {
+        let mut kernels = Vec::new();
+        let mut diagnostics = Vec::new();
+        for ctx in context.contexts() {
+            let (k, d) = self.build_kernels(ctx)?;
+            kernels.push(k);
+            diagnostics.push(d);
+        }
+        let rates = vec![0.0; kernels.len()];
+        Ok(MultiWorker {
+            programs: self.into_programs(),
+            diagnostics, kernels, rates,
+            max_passes: 16,
+            phantom: PhantomData,
+        })
+    }
+}
+
+/// Number of passes for each device so that all devices spend about the same time
+/// as the slowest one spends on a single pass.
+fn balance(rates: &[f64], max_passes: usize) -> Vec<usize> {
+    let min = rates.iter().cloned().filter(|r| *r > 0.0).fold(f64::INFINITY, f64::min);
+    rates.iter().map(|r| {
+        if min.is_finite() && *r > 0.0 {
+            ((r/min).round() as usize).max(1).min(max_passes)
+        } else {
+            1
+        }
+    }).collect()
+}
+
+impl<S: Scene, V: View<Space = S::Space>> MultiWorker<S, V> {
+    pub fn programs(&self) -> &Programs<Program> {
+        &self.programs
+    }
+    /// Build diagnostics for each device.
+    pub fn diagnostics(&self) -> &[Programs<Vec<BuildDiagnostic>>] {
+        &self.diagnostics
+    }
+    /// Measured throughput of each device in passes per second.
+    pub fn rates(&self) -> &[f64] {
+        &self.rates
+    }
+    /// Sets the maximum number of passes a single device renders per frame.
+    pub fn set_max_passes(&mut self, max_passes: usize) {
+        self.max_passes = max_passes.max(1);
+    }
+
+    /// Renders passes on all devices in parallel.
+    ///
+    /// The scenes must be created for the corresponding contexts of `MultiContext`.
+    /// The view is shared between devices, so it must not hold device buffers.
+    pub fn render(
+        &mut self,
+        screen: &mut MultiScreen,
+        scenes: &[S],
+        view: &V,
+    ) -> crate::Result<()>
+    where S: Sync, V: Sync {
+        let n = self.kernels.len();
+        if scenes.len() != n || screen.screens().len() != n {
+            return Err("number of scenes or screens doesn't match number of devices".into());
+        }
+        let passes = balance(&self.rates, self.max_passes);
+
+        let results = thread::scope(|s| {
+            let handles = self.kernels.iter_mut()
+            .zip(screen.screens_mut().iter_mut())
+            .zip(scenes.iter().zip(passes.iter()))
+            .map(|((kernels, screen), (scene, &n_passes))| s.spawn(move || {
+                let start = Instant::now();
+                let tile = Tile { offset: (0, 0), dims: screen.dims() };
+                for _ in 0..n_passes {
+                    kernels.render(screen, &tile, screen.dims(), scene, view)?;
+                }
+                Ok(start.elapsed())
+            }))
+            .collect::<Vec<_>>();
+            handles.into_iter().map(|h| {
+                h.join().unwrap_or_else(|_| Err("render thread panicked".into()))
+            }).collect::<Vec<crate::Result<_>>>()
+        });
+
+        for ((rate, result), n_passes) in self.rates.iter_mut().zip(results).zip(passes) {
+            let secs = result?.as_secs_f64();
+            if secs > 0.0 {
+                let r = n_passes as f64/secs;
+                *rate = if *rate > 0.0 { 0.8*(*rate) + 0.2*r } else { r };
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn balance_passes() {
+        assert_eq!(balance(&[0.0, 0.0], 16), vec![1, 1]);
+        assert_eq!(balance(&[10.0, 31.0, 1000.0], 16), vec![1, 3, 16]);
+    }
+}
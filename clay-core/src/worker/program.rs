@@ -1,80 +1,173 @@
 use std::{
-    path::Path,
+    path::{Path, PathBuf},
+    ffi::CString,
+    collections::HashSet,
 };
-use regex::{Regex, RegexBuilder, Captures};
+use regex::{Regex, RegexBuilder};
 use ocl::{
     self,
-    enums::{ProgramBuildInfo as Pbi, ProgramBuildInfoResult as Pbir},
+    core::{self, ProgramBuildInfo as Pbi, ProgramBuildInfoResult as Pbir},
+    enums::{ProgramInfo as Pi, ProgramInfoResult as Pir},
 };
 use ocl_include::{self, Hook};
 use lazy_static::lazy_static;
-use crate::{Context};
+use crate::{Context, Error};
+use super::{ProgramCache, BuildDiagnostic};
 
 
 lazy_static!{
-    static ref LOCATION: Regex = RegexBuilder::new(
-        r#"^([^:\r\n]*):(\d*):(\d*):"#
+    static ref INCLUDE: Regex = RegexBuilder::new(
+        r#"^\s*#include\s*([<"])(.*)[>"]\s*$"#
     ).multi_line(true).build().unwrap();
 }
 
 pub struct Program {
     source: String,
     index: ocl_include::Index,
+    includes: Vec<(PathBuf, PathBuf)>,
+}
+
+/// Collects `(parent, child)` pairs of the include tree starting from `main` file.
+fn include_edges<H: Hook>(hook: &H, main: &Path) -> crate::Result<Vec<(PathBuf, PathBuf)>> {
+    let mut edges = Vec::new();
+    let (path, text) = hook.read(main, None)?;
+    let mut visited = HashSet::new();
+    let mut stack = vec![(path, text)];
+    while let Some((path, text)) = stack.pop() {
+        if !visited.insert(path.clone()) {
+            continue;
+        }
+        for cap in INCLUDE.captures_iter(&text) {
+            // Quoted includes are relative to the directory of the including file
+            let dir = match &cap[1] {
+                "\"" => path.parent().map(|d| d.to_path_buf()),
+                _ => None,
+            };
+            let (inc_path, inc_text) = hook.read(Path::new(&cap[2]), dir.as_deref())?;
+            edges.push((path.clone(), inc_path.clone()));
+            stack.push((inc_path, inc_text));
+        }
+    }
+    Ok(edges)
 }
 
 impl Program {
     pub fn new<H: Hook>(hook: &H, main: &Path) -> crate::Result<Self> {
         let node = ocl_include::build(hook, main)?;
         let (source, index) = node.collect();
+        let includes = include_edges(hook, main)?;
 
-        Ok(Self { source, index })
+        Ok(Self { source, index, includes })
     }
 
     pub fn source(&self) -> String {
         self.source.clone()
     }
 
-    fn replace_index(&self, message: &str) -> String {
-        LOCATION.replace_all(&message, |caps: &Captures| -> String {
-            caps[2].parse::<usize>().map_err(|_| ())
-            .and_then(|line| {
-                self.index.search(line - 1).ok_or(())
-            })
-            .and_then(|(path, local_line)| {
-                Ok(format!(
-                    "{}:{}:{}:",
-                    path.to_string_lossy(),
-                    local_line + 1,
-                    &caps[3],
-                ))
-            })
-            .unwrap_or(caps[0].to_string())
-        }).into_owned()
+    /// Include dependency graph of the program in DOT format.
+    pub fn include_graph(&self) -> String {
+        let mut lines = vec!["digraph includes {".to_string()];
+        for (parent, child) in self.includes.iter() {
+            lines.push(format!(
+                "    \"{}\" -> \"{}\";",
+                parent.to_string_lossy(), child.to_string_lossy(),
+            ));
+        }
+        lines.push("}".to_string());
+        lines.join("\n")
     }
 
-    pub fn build(&self, context: &Context) -> crate::Result<(ocl::Program, String)> {
-        ocl::Program::builder()
-        .devices(context.device())
-        .source(self.source.clone())
-        .build(context.context())
-        .and_then(|p| {
-            p.build_info(context.device().clone(), Pbi::BuildLog)
-            .map(|pbi| match pbi {
-                Pbir::BuildLog(s) => s,
-                _ => unreachable!(),
-            })
-            .map(|log| {
-                if log.len() > 0 {
-                    println!("Build log: {}", log);
-                }
-                (p, self.replace_index(&log))
-            })
-            .map_err(|e| e.into())
-        })
-        .map_err(|e| {
-            let message = self.replace_index(&e.to_string());
-            ocl::Error::from(ocl::core::Error::from(message))
+    /// Parses the build log mapping locations to the original source files.
+    pub fn diagnostics(&self, log: &str) -> Vec<BuildDiagnostic> {
+        BuildDiagnostic::parse(log, |line| {
+            if line == 0 {
+                return None;
+            }
+            self.index.search(line - 1)
+            .map(|(path, local_line)| (path, local_line + 1))
         })
-        .map_err(|e| e.into())
+    }
+
+    /// Builds created program and collects its diagnostics.
+    ///
+    /// Build failure is reported as `Error::Build` with diagnostics from the log.
+    fn build_core(
+        &self,
+        program: core::Program,
+        context: &Context,
+    ) -> crate::Result<(ocl::Program, Vec<BuildDiagnostic>)> {
+        let device = context.device();
+        let options = CString::new("").unwrap();
+        let result = core::build_program(&program, Some(&[device]), &options, None, None);
+        let log = match core::get_program_build_info(&program, device, Pbi::BuildLog) {
+            Ok(Pbir::BuildLog(log)) => log,
+            _ => String::new(),
+        };
+        let mut diagnostics = self.diagnostics(&log);
+        match result {
+            Ok(()) => Ok((program.into(), diagnostics)),
+            Err(e) => {
+                if !diagnostics.iter().any(|d| d.severity == super::Severity::Error) {
+                    diagnostics.push(BuildDiagnostic::error(&e.to_string()));
+                }
+                Err(Error::Build(diagnostics))
+            },
+        }
+    }
+
+    pub fn build(&self, context: &Context) -> crate::Result<(ocl::Program, Vec<BuildDiagnostic>)> {
+        let source = CString::new(self.source.clone()).map_err(|e| e.to_string())?;
+        let program = core::create_program_with_source(context.context(), &[source])
+        .map_err(ocl::Error::from)?;
+        self.build_core(program, context)
+    }
+
+    /// Builds the program using the binary from cache if it is there,
+    /// otherwise builds it from source and stores the binary in cache.
+    pub fn build_cached(
+        &self,
+        context: &Context,
+        cache: &ProgramCache,
+    ) -> crate::Result<(ocl::Program, Vec<BuildDiagnostic>)> {
+        let key = ProgramCache::key(&self.source, context.device())?;
+        if let Some(binary) = cache.load(key) {
+            let program = core::create_program_with_binary(
+                context.context(), &[context.device()], &[binary.as_slice()],
+            );
+            // Fall back to source compilation if the binary is rejected
+            if let Ok(p) = program {
+                if let Ok(r) = self.build_core(p, context) {
+                    return Ok(r);
+                }
+            }
+        }
+
+        let (program, diagnostics) = self.build(context)?;
+        if let Pir::Binaries(binaries) = program.info(Pi::Binaries).map_err(ocl::Error::from)? {
+            if let Some(binary) = binaries.first() {
+                // Failure to store the binary only makes the next build slower
+                let _ = cache.store(key, binary);
+            }
+        }
+        Ok((program, diagnostics))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use ocl_include::MemHook;
+    use super::*;
+
+    #[test]
+    fn include_graph() {
+        let hook = MemHook::builder()
+        .add_file(&Path::new("main.c"), "#include <lib/a.h>\n#include <lib/b.h>".to_string()).unwrap()
+        .add_file(&Path::new("lib/a.h"), "#pragma once\n#include \"b.h\"".to_string()).unwrap()
+        .add_file(&Path::new("lib/b.h"), "#pragma once".to_string()).unwrap()
+        .build();
+        let graph = Program::new(&hook, &Path::new("main.c")).unwrap().include_graph();
+        assert!(graph.contains("\"main.c\" -> \"lib/a.h\";"));
+        assert!(graph.contains("\"main.c\" -> \"lib/b.h\";"));
+        assert!(graph.contains("\"lib/a.h\" -> \"lib/b.h\";"));
     }
 }
@@ -0,0 +1,87 @@
+/// Rectangular part of the frame.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Tile {
+    pub offset: (usize, usize),
+    pub dims: (usize, usize),
+}
+
+impl Tile {
+    /// Copies RGB bytes of the tile from the `src` buffer with row width `src_width`
+    /// to its place in the `dst` frame with row width `dst_width`.
+    pub fn copy_to(&self, src: &[u8], src_width: usize, dst: &mut [u8], dst_width: usize) {
+        let row = 3*self.dims.0;
+        for y in 0..self.dims.1 {
+            let s = 3*y*src_width;
+            let d = 3*((self.offset.1 + y)*dst_width + self.offset.0);
+            dst[d..(d + row)].copy_from_slice(&src[s..(s + row)]);
+        }
+    }
+}
+
+/// Iterator over tiles covering the frame row by row.
+///
+/// Tiles at the right and bottom edges are cropped to fit the frame.
+pub struct Tiles {
+    frame: (usize, usize),
+    tile: (usize, usize),
+    index: usize,
+}
+
+impl Tiles {
+    pub fn new(frame: (usize, usize), tile: (usize, usize)) -> Self {
+        assert!(tile.0 > 0 && tile.1 > 0, "tile size must be non-zero");
+        Self { frame, tile, index: 0 }
+    }
+
+    fn grid(&self) -> (usize, usize) {
+        (self.frame.0.div_ceil(self.tile.0), self.frame.1.div_ceil(self.tile.1))
+    }
+}
+
+impl Iterator for Tiles {
+    type Item = Tile;
+
+    fn next(&mut self) -> Option<Tile> {
+        let grid = self.grid();
+        if self.index >= grid.0*grid.1 {
+            return None;
+        }
+        let (x, y) = (self.index % grid.0, self.index / grid.0);
+        self.index += 1;
+        let offset = (x*self.tile.0, y*self.tile.1);
+        Some(Tile {
+            offset,
+            dims: (
+                self.tile.0.min(self.frame.0 - offset.0),
+                self.tile.1.min(self.frame.1 - offset.1),
+            ),
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let grid = self.grid();
+        let n = grid.0*grid.1 - self.index;
+        (n, Some(n))
+    }
+}
+
+impl ExactSizeIterator for Tiles {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn cover() {
+        let frame = (5, 3);
+        let tiles = Tiles::new(frame, (2, 2));
+        assert_eq!(tiles.len(), 6);
+
+        let mut image = vec![0u8; 3*frame.0*frame.1];
+        for tile in tiles {
+            let src = vec![1u8; 3*2*2];
+            tile.copy_to(&src, 2, &mut image, frame.0);
+        }
+        assert!(image.iter().all(|&b| b == 1));
+    }
+}
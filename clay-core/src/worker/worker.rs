@@ -1,88 +1,223 @@
 use std::{
-    path::Path,
+    env, fs,
+    path::{Path, PathBuf},
     collections::HashSet,
     marker::PhantomData,
+    time::{Duration, Instant},
 };
-use ocl::{self, prm};
+use ocl::{self, prm, enums::ProfilingInfo};
 use ocl_include::{Hook, MemHook, ListHook};
-use crate::{Context, Scene, View, Screen};
-use super::{Program};
+use crate::{Context, Scene, Space, View, Screen, Stats, Aov, aov_source, export::save_ppm};
+use super::{Program, ProgramCache, BuildDiagnostic, Tile, Tiles, Denoise, Denoiser, Adaptive, Sampler};
+
+/// Environment variable that sets the directory for dumping generated sources.
+pub const DUMP_DIR_VAR: &str = "CLAY_DUMP_DIR";
 
 pub struct Programs<P> {
     pub render: P,
     pub draw: P,
 }
 
-pub struct Kernels {
+/// Kernels built for a single device.
+pub(crate) struct Kernels {
     render: ocl::Kernel,
     draw: ocl::Kernel,
+    denoiser: Denoiser,
+    sampler: Sampler,
+    queue: ocl::Queue,
+    /// Per-pixel counter of traced rays, kernel skips counting if not set.
+    ray_count: Option<ocl::Buffer<u32>>,
+    /// Output variables enabled in the render program.
+    aovs: Vec<Aov>,
+    /// Time interval the ray times are sampled from.
+    shutter: (f64, f64),
+}
+
+/// Statistics being collected by the worker.
+struct StatsCollector {
+    stats: Stats,
+    start: Instant,
+    /// Events of `render` and `draw` kernels not yet accounted in stats.
+    pending: Vec<(ocl::Event, ocl::Event)>,
+}
+
+/// Kernel execution time if the queue has profiling enabled.
+fn event_time(event: &ocl::Event) -> Option<Duration> {
+    let start = event.profiling_info(ProfilingInfo::Start).ok()?.time().ok()?;
+    let end = event.profiling_info(ProfilingInfo::End).ok()?.time().ok()?;
+    Some(Duration::from_nanos(end.saturating_sub(start)))
+}
+
+impl StatsCollector {
+    fn new() -> Self {
+        Self { stats: Stats::default(), start: Instant::now(), pending: Vec::new() }
+    }
+
+    /// Accounts kernel times of completed events.
+    fn update(&mut self) -> crate::Result<()> {
+        let mut pending = Vec::new();
+        for (render, draw) in self.pending.drain(..) {
+            if render.is_complete()? && draw.is_complete()? {
+                self.stats.render_time += event_time(&render).unwrap_or_default();
+                self.stats.draw_time += event_time(&draw).unwrap_or_default();
+            } else {
+                pending.push((render, draw));
+            }
+        }
+        self.pending = pending;
+        Ok(())
+    }
 }
 
-pub struct WorkerCollector<S: Scene, V: View> {
+pub struct WorkerCollector<S: Scene, V: View<Space = S::Space>> {
     hooks: ListHook,
+    dump_dir: Option<PathBuf>,
+    aovs: Vec<Aov>,
     phantom: PhantomData<(S, V)>,
 }
 
-pub struct WorkerBuilder<S: Scene, V: View> {
+pub struct WorkerBuilder<S: Scene, V: View<Space = S::Space>> {
     programs: Programs<Program>,
+    generated: Vec<(PathBuf, String)>,
+    cache: Option<ProgramCache>,
+    aovs: Vec<Aov>,
     phantom: PhantomData<(S, V)>,
 }
 
 #[allow(dead_code)]
-pub struct Worker<S: Scene, V: View> {
-    programs: Programs<(Program, String)>,
+pub struct Worker<S: Scene, V: View<Space = S::Space>> {
+    programs: Programs<(Program, Vec<BuildDiagnostic>)>,
     kernels: Kernels,
-    queue: ocl::Queue,
+    stats: Option<StatsCollector>,
     phantom: PhantomData<(S, V)>,
 }
 
-impl<S: Scene, V: View> WorkerBuilder<S, V> {
+impl<S: Scene, V: View<Space = S::Space>> WorkerBuilder<S, V> {
     pub fn programs(&self) -> &Programs<Program> {
         &self.programs
     }
+
+    pub(crate) fn into_programs(self) -> Programs<Program> {
+        self.programs
+    }
+
+    /// Writes the flattened program sources, generated headers
+    /// and include graphs of programs in DOT format to the directory.
+    pub fn dump<P: AsRef<Path>>(&self, dir: P) -> crate::Result<()> {
+        let dir = dir.as_ref();
+        for (path, text) in self.generated.iter() {
+            let path = dir.join(path);
+            if let Some(parent) = path.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::write(path, text)?;
+        }
+        for (name, prog) in [
+            ("render", &self.programs.render),
+            ("draw", &self.programs.draw),
+        ].iter() {
+            fs::write(dir.join(format!("{}.c", name)), prog.source())?;
+            fs::write(dir.join(format!("{}.dot", name)), prog.include_graph())?;
+        }
+        Ok(())
+    }
+
+    /// Sets the cache of compiled programs, `None` disables caching.
+    ///
+    /// By default the cache is stored in `ProgramCache::default_dir()`.
+    pub fn cache(&mut self, cache: Option<ProgramCache>) -> &mut Self {
+        self.cache = cache;
+        self
+    }
 }
 
-impl<S: Scene, V: View> Worker<S, V> {
+impl<S: Scene, V: View<Space = S::Space>> Worker<S, V> {
+    /// Creates the collector with the sources of `clay_core` and of the space of the scene.
     pub fn builder() -> WorkerCollector<S, V> {
+        let mut hooks = ListHook::builder().add_hook(crate::source());
+        if let Some(hook) = S::Space::hook() {
+            hooks = hooks.add_hook(hook);
+        }
         WorkerCollector {
-            hooks:
-                ListHook::builder()
-                .add_hook(crate::source())
-                .build(),
+            hooks: hooks.build(),
+            dump_dir: env::var_os(DUMP_DIR_VAR).map(PathBuf::from),
+            aovs: Vec::new(),
             phantom: PhantomData,
         }
     }
 }
 
-impl<S: Scene, V: View> WorkerCollector<S, V> {
+impl<S: Scene, V: View<Space = S::Space>> WorkerCollector<S, V> {
     pub fn add_hook<H: Hook + 'static>(&mut self, hook: H) {
         self.hooks.add_hook(hook);
     }
 
+    /// Sets the directory to dump generated sources to on collection, `None` disables dumping.
+    ///
+    /// By default the directory is taken from `CLAY_DUMP_DIR` environment variable.
+    pub fn dump_dir<P: AsRef<Path>>(&mut self, dir: Option<P>) -> &mut Self {
+        self.dump_dir = dir.map(|d| d.as_ref().to_path_buf());
+        self
+    }
+
+    /// Sets the output variables written by the render kernel.
+    ///
+    /// Screens passed to the worker must be created with buffers for these variables.
+    pub fn aovs(&mut self, aovs: &[Aov]) -> &mut Self {
+        self.aovs = aovs.to_vec();
+        self
+    }
+
     pub fn collect(mut self) -> crate::Result<WorkerBuilder<S, V>> {
         let mut inst_cache = HashSet::<u64>::new();
-        self.hooks.add_hook(
-            MemHook::builder()
-            .add_file(&Path::new("__gen/scene.h"), S::source(&mut inst_cache))?
-            .add_file(&Path::new("__gen/view.h"), V::source(&mut inst_cache))?
-            .build()
-        );
+        let generated = vec![
+            (PathBuf::from("__gen/space.h"), S::Space::source()),
+            (PathBuf::from("__gen/scene.h"), S::source(&mut inst_cache)),
+            (PathBuf::from("__gen/view.h"), V::source(&mut inst_cache)),
+            (PathBuf::from("__gen/aov.h"), aov_source(&self.aovs)),
+        ];
+        let mut mem_hook = MemHook::builder();
+        for (path, text) in generated.iter() {
+            mem_hook = mem_hook.add_file(path, text.clone())?;
+        }
+        self.hooks.add_hook(mem_hook.build());
         let render_prog = Program::new(&self.hooks, &Path::new("clay_core/render.c"))?;
         let draw_prog = Program::new(&self.hooks, &Path::new("clay_core/draw.c"))?;
 
-        Ok(WorkerBuilder {
+        let builder = WorkerBuilder {
             programs: Programs { render: render_prog, draw: draw_prog },
+            generated,
+            cache: Some(ProgramCache::default()),
+            aovs: self.aovs,
             phantom: PhantomData,
-        })
+        };
+        if let Some(dir) = &self.dump_dir {
+            builder.dump(dir)?;
+        }
+        Ok(builder)
     }
 }
 
-impl<S: Scene, V: View> WorkerBuilder<S, V> {
-    pub fn build(self, context: &Context) -> crate::Result<Worker<S, V>> {
+impl<S: Scene, V: View<Space = S::Space>> WorkerBuilder<S, V> {
+    fn build_program(
+        program: &Program,
+        context: &Context,
+        cache: &Option<ProgramCache>,
+    ) -> crate::Result<(ocl::Program, Vec<BuildDiagnostic>)> {
+        match cache {
+            Some(cache) => program.build_cached(context, cache),
+            None => program.build(context),
+        }
+    }
+
+    /// Builds programs and creates kernels for the context.
+    pub(crate) fn build_kernels(
+        &self,
+        context: &Context,
+    ) -> crate::Result<(Kernels, Programs<Vec<BuildDiagnostic>>)> {
         let queue = context.queue().clone();
 
-        let render_prog = self.programs.render;
-        let ocl_render_prog = render_prog.build(context)?;
+        let ocl_render_prog = Self::build_program(&self.programs.render, context, &self.cache)?;
 
         // build kernel
         let mut kb = ocl::Kernel::builder();
@@ -90,15 +225,27 @@ impl<S: Scene, V: View> WorkerBuilder<S, V> {
         .name("render")
         .queue(queue.clone())
         .arg(prm::Int2::zero()) // screen size
+        .arg(prm::Int2::zero()) // tile offset
+        .arg(prm::Int2::zero()) // frame size
         .arg(None::<&ocl::Buffer<prm::Float3>>) // color buffer
-        .arg(None::<&ocl::Buffer<u32>>); // random
+        .arg(None::<&ocl::Buffer<u32>>) // random
+        .arg(None::<&ocl::Buffer<u32>>) // ray count
+        .arg(None::<&ocl::Buffer<f32>>) // depth AOV
+        .arg(None::<&ocl::Buffer<f32>>) // normal AOV
+        .arg(None::<&ocl::Buffer<f32>>) // albedo AOV
+        .arg(None::<&ocl::Buffer<f32>>) // object id AOV
+        .arg(None::<&ocl::Buffer<f32>>) // samples AOV
+        .arg(0i32) // passes
+        .arg(None::<&ocl::Buffer<f32>>) // squares
+        .arg(None::<&ocl::Buffer<u32>>) // sample count
+        .arg(None::<&ocl::Buffer<u8>>) // mask
+        .arg(prm::Float2::zero()); // shutter
         V::args_def(&mut kb);
         S::args_def(&mut kb);
         let render_kernel = kb.build()?;
 
         // draw program
-        let draw_prog = self.programs.draw;
-        let ocl_draw_prog = draw_prog.build(context)?;
+        let ocl_draw_prog = Self::build_program(&self.programs.draw, context, &self.cache)?;
         let draw_kernel = ocl::Kernel::builder()
         .program(&ocl_draw_prog.0)
         .name("draw")
@@ -108,37 +255,78 @@ impl<S: Scene, V: View> WorkerBuilder<S, V> {
         .arg(None::<&ocl::Buffer<prm::Float3>>) // color buffer
         .arg(None::<&ocl::Buffer<u8>>) // screen
         .build()?;
+        let denoiser = Denoiser::new(&ocl_draw_prog.0, &queue)?;
+        let sampler = Sampler::new(&ocl_draw_prog.0, &queue)?;
+
+        Ok((
+            Kernels {
+                render: render_kernel, draw: draw_kernel, denoiser, sampler, queue,
+                ray_count: None, aovs: self.aovs.clone(), shutter: (0.0, 0.0),
+            },
+            Programs { render: ocl_render_prog.1, draw: ocl_draw_prog.1 },
+        ))
+    }
 
+    pub fn build(self, context: &Context) -> crate::Result<Worker<S, V>> {
+        let (kernels, diagnostics) = self.build_kernels(context)?;
         Ok(Worker {
             programs: Programs {
-                render: (render_prog, ocl_render_prog.1),
-                draw: (draw_prog, ocl_draw_prog.1),
+                render: (self.programs.render, diagnostics.render),
+                draw: (self.programs.draw, diagnostics.draw),
             },
-            kernels: Kernels { render: render_kernel, draw: draw_kernel },
-            queue, phantom: PhantomData,
+            kernels, stats: None,
+            phantom: PhantomData,
         })
     }
 }
 
-impl<S: Scene, V: View> Worker<S, V> {
-    pub fn programs(&self) -> &Programs<(Program, String)> {
-        &self.programs
-    }
-
-    pub fn render(
+impl Kernels {
+    /// Enqueues a single render pass accumulating it in the screen.
+    ///
+    /// The screen contains the tile of `tile.dims` size placed at `tile.offset` of the `frame`.
+    pub(crate) fn enqueue_render<S: Scene, V: View>(
         &mut self,
         screen: &mut Screen,
+        tile: &Tile,
+        frame: (usize, usize),
         scene: &S,
         view: &V,
-    ) -> crate::Result<()> {
-        let kernel = &mut self.kernels.render;
+    ) -> crate::Result<ocl::Event> {
+        let adaptive = self.sampler.enqueue(screen, tile.dims)?;
+        let kernel = &mut self.render;
 
         let dims = screen.dims();
         let dims = prm::Int2::new(dims.0 as i32, dims.1 as i32);
         kernel.set_arg(0, &dims)?;
-        kernel.set_arg(1, screen.color_mut())?;
-        kernel.set_arg(2, screen.random_mut())?;
-        let mut i = 3;
+        kernel.set_arg(1, &prm::Int2::new(tile.offset.0 as i32, tile.offset.1 as i32))?;
+        kernel.set_arg(2, &prm::Int2::new(frame.0 as i32, frame.1 as i32))?;
+        kernel.set_arg(3, screen.color_mut())?;
+        kernel.set_arg(4, screen.random_mut())?;
+        match &self.ray_count {
+            Some(buffer) if buffer.len() >= screen.len() => kernel.set_arg(5, buffer)?,
+            _ => kernel.set_arg(5, None::<&ocl::Buffer<u32>>)?,
+        }
+        for (k, aov) in Aov::all().iter().enumerate() {
+            if self.aovs.contains(aov) {
+                let buffer = screen.aov(*aov).ok_or_else(|| {
+                    format!("screen has no buffer for '{}' AOV", aov.name())
+                })?;
+                kernel.set_arg(6 + k, buffer)?;
+            } else {
+                kernel.set_arg(6 + k, None::<&ocl::Buffer<f32>>)?;
+            }
+        }
+        let mut i = 6 + Aov::all().len();
+        kernel.set_arg(i, screen.n_passes() as i32)?;
+        kernel.set_arg(i + 1, screen.squares())?;
+        kernel.set_arg(i + 2, screen.sample_count())?;
+        if adaptive {
+            kernel.set_arg(i + 3, screen.mask())?;
+        } else {
+            kernel.set_arg(i + 3, None::<&ocl::Buffer<u8>>)?;
+        }
+        kernel.set_arg(i + 4, prm::Float2::new(self.shutter.0 as f32, self.shutter.1 as f32))?;
+        i += 5;
 
         view.args_set(i, kernel)?;
         i += V::args_count();
@@ -146,34 +334,315 @@ impl<S: Scene, V: View> Worker<S, V> {
         scene.args_set(i, kernel)?;
         //i += S::args_count();
 
+        let mut event = ocl::Event::empty();
         unsafe {
             kernel
             .cmd()
-            .global_work_size(screen.dims())
+            .global_work_size(tile.dims)
+            .enew(&mut event)
             .enq()?;
         }
 
-        self.queue.finish()?;
-
         screen.pass();
 
-        let kernel = &mut self.kernels.draw;
+        Ok(event)
+    }
+
+    /// Enqueues conversion of accumulated color into screen bytes after the `wait` event.
+    ///
+    /// The color is denoised before the conversion if the denoiser is enabled.
+    /// The bytes are written to the back buffer of the screen, it becomes the front one.
+    pub(crate) fn enqueue_draw(
+        &mut self,
+        screen: &mut Screen,
+        wait: Option<&ocl::Event>,
+    ) -> crate::Result<ocl::Event> {
+        let kernel = &mut self.draw;
         let dims = screen.dims();
         let dims = prm::Int2::new(dims.0 as i32, dims.1 as i32);
         kernel.set_arg(0, &dims)?;
-        kernel.set_arg(1, &(screen.n_passes() as i32))?;
-        kernel.set_arg(2, screen.color_mut())?;
-        kernel.set_arg(3, screen.bytes_mut())?;
+        let denoised = self.denoiser.enqueue(screen, wait)?;
+        let wait = match denoised {
+            // Denoised color is already averaged over passes
+            Some((color, event)) => {
+                kernel.set_arg(1, &1i32)?;
+                kernel.set_arg(2, color)?;
+                Some(event)
+            },
+            None => {
+                kernel.set_arg(1, &(screen.n_passes() as i32))?;
+                kernel.set_arg(2, screen.color())?;
+                wait.cloned()
+            },
+        };
+        kernel.set_arg(3, screen.back_bytes_mut())?;
 
+        let mut event = ocl::Event::empty();
         unsafe {
             kernel
             .cmd()
             .global_work_size(screen.dims())
+            .ewait(wait.as_ref())
+            .enew(&mut event)
             .enq()?;
         }
+        screen.swap(event.clone());
+
+        Ok(event)
+    }
 
-        self.queue.finish()?;
+    /// Sums and resets the ray counter.
+    fn take_ray_count(&mut self) -> crate::Result<u64> {
+        let buffer = match &self.ray_count {
+            Some(buffer) => buffer,
+            None => return Ok(0),
+        };
+        let mut counts = vec![0u32; buffer.len()];
+        buffer.cmd().offset(0).read(&mut counts).enq()?;
+        buffer.cmd().offset(0).fill(0u32, None).enq()?;
+        Ok(counts.iter().map(|&c| c as u64).sum())
+    }
 
+    /// Creates the ray counter large enough for the screen.
+    fn reserve_ray_count(&mut self, len: usize) -> crate::Result<u64> {
+        if self.ray_count.as_ref().is_some_and(|b| b.len() >= len) {
+            return Ok(0);
+        }
+        let count = self.take_ray_count()?;
+        self.ray_count = Some(
+            ocl::Buffer::<u32>::builder()
+            .queue(self.queue.clone())
+            .flags(ocl::flags::MEM_READ_WRITE)
+            .len(len)
+            .fill_val(0u32)
+            .build()?
+        );
+        Ok(count)
+    }
+
+    /// Renders a single pass and waits for it to complete.
+    pub(crate) fn render<S: Scene, V: View>(
+        &mut self,
+        screen: &mut Screen,
+        tile: &Tile,
+        frame: (usize, usize),
+        scene: &S,
+        view: &V,
+    ) -> crate::Result<()> {
+        self.enqueue_render(screen, tile, frame, scene, view)?.wait_for()?;
         Ok(())
     }
+
+    /// Draws the screen and waits for it to complete.
+    pub(crate) fn draw(&mut self, screen: &mut Screen) -> crate::Result<()> {
+        self.enqueue_draw(screen, None)?.wait_for()?;
+        Ok(())
+    }
+}
+
+/// Handle of the enqueued rendering.
+///
+/// `Screen::read` waits for the rendering, while `Screen::read_ready` reads
+/// the previous image until the rendering is complete.
+#[must_use]
+pub struct RenderHandle {
+    event: ocl::Event,
+}
+
+impl RenderHandle {
+    /// Checks whether the rendering is complete without blocking.
+    pub fn is_complete(&self) -> crate::Result<bool> {
+        Ok(self.event.is_complete()?)
+    }
+
+    /// Blocks until the rendering is complete.
+    pub fn wait(self) -> crate::Result<()> {
+        Ok(self.event.wait_for()?)
+    }
+
+    pub fn event(&self) -> &ocl::Event {
+        &self.event
+    }
+}
+
+impl<S: Scene, V: View<Space = S::Space>> Worker<S, V> {
+    pub fn programs(&self) -> &Programs<(Program, Vec<BuildDiagnostic>)> {
+        &self.programs
+    }
+
+    pub fn render(
+        &mut self,
+        screen: &mut Screen,
+        scene: &S,
+        view: &V,
+    ) -> crate::Result<()> {
+        self.enqueue(screen, scene, view)?.wait()
+    }
+
+    /// Enqueues render and draw passes without waiting for them.
+    ///
+    /// Calls of this method could be chained, the device executes them in order.
+    pub fn enqueue(
+        &mut self,
+        screen: &mut Screen,
+        scene: &S,
+        view: &V,
+    ) -> crate::Result<RenderHandle> {
+        if let Some(collector) = &mut self.stats {
+            collector.stats.rays += self.kernels.reserve_ray_count(screen.len())?;
+        }
+        let tile = Tile { offset: (0, 0), dims: screen.dims() };
+        let render = self.kernels.enqueue_render(screen, &tile, screen.dims(), scene, view)?;
+        let draw = self.kernels.enqueue_draw(screen, Some(&render))?;
+        if let Some(collector) = &mut self.stats {
+            collector.stats.passes += 1;
+            collector.stats.paths += screen.len() as u64;
+            collector.pending.push((render, draw.clone()));
+            // Don't let the completed events pile up if the stats are rarely requested
+            if collector.pending.len() > 64 {
+                collector.update()?;
+            }
+        }
+        Ok(RenderHandle { event: draw })
+    }
+
+    /// Denoiser settings, the denoiser is disabled by default.
+    pub fn denoise(&self) -> &Denoise {
+        &self.kernels.denoiser.settings
+    }
+    pub fn denoise_mut(&mut self) -> &mut Denoise {
+        &mut self.kernels.denoiser.settings
+    }
+    pub fn set_denoise(&mut self, denoise: Denoise) {
+        self.kernels.denoiser.settings = denoise;
+    }
+
+    /// Adaptive sampling settings, adaptive sampling is disabled by default.
+    pub fn adaptive(&self) -> &Adaptive {
+        &self.kernels.sampler.settings
+    }
+    pub fn adaptive_mut(&mut self) -> &mut Adaptive {
+        &mut self.kernels.sampler.settings
+    }
+    pub fn set_adaptive(&mut self, adaptive: Adaptive) {
+        self.kernels.sampler.settings = adaptive;
+    }
+
+    /// Shutter interval, all rays have the time of the shutter opening by default.
+    pub fn shutter(&self) -> (f64, f64) {
+        self.kernels.shutter
+    }
+    /// Sets the interval the time of each pixel sample is uniformly chosen from.
+    ///
+    /// Objects moved by animated maps (e.g. `MovingShift`) are blurred along their path.
+    pub fn set_shutter(&mut self, open: f64, close: f64) {
+        assert!(open <= close, "shutter must not close before it opens");
+        self.kernels.shutter = (open, close);
+    }
+
+    /// Renders passes until all pixels converge or the screen has `max_passes` passes.
+    ///
+    /// Requires adaptive sampling to be enabled. Returns whether the image has converged.
+    pub fn render_converged(
+        &mut self,
+        screen: &mut Screen,
+        scene: &S,
+        view: &V,
+        max_passes: usize,
+    ) -> crate::Result<bool> {
+        if !self.adaptive().enabled {
+            return Err("adaptive sampling is disabled".into());
+        }
+        while screen.n_passes() < max_passes {
+            self.enqueue(screen, scene, view)?.wait()?;
+            if screen.unconverged()? == 0 {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    /// Starts collecting statistics from scratch or stops it.
+    pub fn collect_stats(&mut self, enabled: bool) {
+        self.kernels.ray_count = None;
+        self.stats = if enabled { Some(StatsCollector::new()) } else { None };
+    }
+
+    /// Statistics collected since `collect_stats(true)` call, `None` if collection is disabled.
+    ///
+    /// Waits for all enqueued passes to complete.
+    pub fn stats(&mut self) -> crate::Result<Option<Stats>> {
+        let collector = match &mut self.stats {
+            Some(collector) => collector,
+            None => return Ok(None),
+        };
+        self.kernels.queue.finish()?;
+        collector.update()?;
+        collector.stats.rays += self.kernels.take_ray_count()?;
+        collector.stats.wall_time = collector.start.elapsed();
+        Ok(Some(collector.stats.clone()))
+    }
+
+    /// Renders the frame tile by tile using the screen as a tile buffer.
+    ///
+    /// Each tile is rendered in `n_passes` passes, or less if adaptive sampling
+    /// is enabled and the tile converges earlier. The `progress` function is called
+    /// with the number of rendered tiles and the total number of tiles after each tile.
+    /// Returns RGB bytes of the whole frame.
+    pub fn render_tiled<F: FnMut(usize, usize)>(
+        &mut self,
+        screen: &mut Screen,
+        frame: (usize, usize),
+        n_passes: usize,
+        scene: &S,
+        view: &V,
+        mut progress: F,
+    ) -> crate::Result<Vec<u8>> {
+        let mut image = vec![0u8; 3*frame.0*frame.1];
+        let tiles = Tiles::new(frame, screen.dims());
+        let count = tiles.len();
+        for (i, tile) in tiles.enumerate() {
+            screen.clear()?;
+            for _ in 0..n_passes {
+                self.kernels.render(screen, &tile, frame, scene, view)?;
+                if self.kernels.sampler.settings.enabled && screen.unconverged()? == 0 {
+                    break;
+                }
+            }
+            self.kernels.draw(screen)?;
+            tile.copy_to(&screen.read()?, screen.dims().0, &mut image, frame.0);
+            progress(i + 1, count);
+        }
+        Ok(image)
+    }
+
+    /// Renders an animation frame by frame and saves the frames to `dir` as numbered PPM images.
+    ///
+    /// For each of the `times` the `frame` function updates the scene and returns the view
+    /// for that moment, e.g. sampled from a camera `Track`. Each frame is rendered
+    /// in `n_passes` passes from scratch. Returns the paths of the saved images.
+    pub fn render_sequence<P, F>(
+        &mut self,
+        screen: &mut Screen,
+        scene: &mut S,
+        times: &[f64],
+        n_passes: usize,
+        dir: P,
+        mut frame: F,
+    ) -> crate::Result<Vec<PathBuf>>
+    where P: AsRef<Path>, F: FnMut(f64, &mut S) -> crate::Result<V> {
+        fs::create_dir_all(&dir)?;
+        let mut paths = Vec::with_capacity(times.len());
+        for (i, time) in times.iter().enumerate() {
+            let view = frame(*time, scene)?;
+            screen.clear()?;
+            for _ in 0..n_passes {
+                self.render(screen, scene, &view)?;
+            }
+            let path = dir.as_ref().join(format!("frame_{:06}.ppm", i));
+            save_ppm(&path, screen.dims(), &screen.read()?)?;
+            paths.push(path);
+        }
+        Ok(paths)
+    }
 }
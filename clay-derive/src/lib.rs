@@ -0,0 +1,309 @@
+extern crate proc_macro;
+
+use proc_macro::TokenStream;
+use proc_macro2::{TokenStream as TokenStream2, Span};
+use quote::quote;
+use syn::{
+    parse_macro_input, parse_quote,
+    DeriveInput, Data, Fields, Type, Path, LitStr, Ident, Index, Error,
+};
+
+
+/// Types of struct fields in the order of declaration.
+fn field_types(input: &DeriveInput) -> syn::Result<Vec<Type>> {
+    match &input.data {
+        Data::Struct(data) => Ok(data.fields.iter().map(|f| f.ty.clone()).collect()),
+        _ => Err(Error::new_spanned(&input.ident, "only structs are supported")),
+    }
+}
+
+/// Expressions accessing struct fields of `self`.
+fn field_accessors(fields: &Fields) -> Vec<TokenStream2> {
+    fields.iter().enumerate().map(|(i, f)| match &f.ident {
+        Some(ident) => quote!(self.#ident),
+        None => {
+            let index = Index::from(i);
+            quote!(self.#index)
+        },
+    }).collect()
+}
+
+/// Names of struct fields in OpenCL.
+///
+/// Name could be set with `#[pack(name = "...")]` attribute,
+/// otherwise Rust field name (or `_<index>` for tuple structs) is used.
+fn field_names(fields: &Fields) -> syn::Result<Vec<String>> {
+    fields.iter().enumerate().map(|(i, f)| {
+        let mut name = match &f.ident {
+            Some(ident) => ident.to_string(),
+            None => format!("_{}", i),
+        };
+        for attr in f.attrs.iter().filter(|a| a.path().is_ident("pack")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("name") {
+                    name = meta.value()?.parse::<LitStr>()?.value();
+                    Ok(())
+                } else {
+                    Err(meta.error("unknown attribute, expected `name`"))
+                }
+            })?;
+        }
+        Ok(name)
+    }).collect()
+}
+
+/// Path to `clay_core` crate in the generated code.
+///
+/// It is `::clay_core` by default and could be set with `#[pack(crate = "...")]` struct attribute,
+/// e.g. `#[pack(crate = "crate")]` to derive traits inside `clay_core` itself.
+fn crate_path(input: &DeriveInput) -> syn::Result<Path> {
+    let mut path = parse_quote!(::clay_core);
+    for attr in input.attrs.iter().filter(|a| a.path().is_ident("pack")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("crate") {
+                path = meta.value()?.parse::<LitStr>()?.parse::<Path>()?;
+                Ok(())
+            } else {
+                Err(meta.error("unknown attribute, expected `crate`"))
+            }
+        })?;
+    }
+    Ok(path)
+}
+
+/// Header of the `Trait` impl with `Field: Bound` added to the where clause for each field type.
+fn impl_header(
+    input: &DeriveInput,
+    trait_: TokenStream2,
+    bound: TokenStream2,
+) -> syn::Result<TokenStream2> {
+    let ident = &input.ident;
+    let mut generics = input.generics.clone();
+    {
+        let where_clause = generics.make_where_clause();
+        for ty in field_types(input)? {
+            where_clause.predicates.push(parse_quote!(#ty: #bound));
+        }
+    }
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    Ok(quote!(impl #impl_generics #trait_ for #ident #ty_generics #where_clause))
+}
+
+fn pack_impl(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let krate = crate_path(input)?;
+    let mut header_input = input.clone();
+    let generic = !input.generics.params.is_empty();
+    if generic {
+        // Type hash is used in the names of the layout
+        header_input.generics.make_where_clause().predicates.push(parse_quote!(Self: 'static));
+    }
+    let header = impl_header(&header_input, quote!(#krate::Pack), quote!(#krate::Pack))?;
+    let types = field_types(input)?;
+    let (fields, names) = match &input.data {
+        Data::Struct(data) => (field_accessors(&data.fields), field_names(&data.fields)?),
+        _ => unreachable!(),
+    };
+    let (name, prefix) = (input.ident.to_string(), snake_case(&input.ident));
+    // Instantiations of generic type have different layouts, so their names are made unique
+    let (name, prefix) = if generic {
+        let hash = quote!(<Self as #krate::TypeHash>::type_hash());
+        (
+            quote!(&format!("{}_{:x}", #name, #hash)),
+            quote!(&format!("{}_{:x}", #prefix, #hash)),
+        )
+    } else {
+        (quote!(#name), quote!(#prefix))
+    };
+    Ok(quote!(
+        #header {
+            fn size_int() -> usize {
+                0 #( + <#types as #krate::Pack>::size_int() )*
+            }
+            fn size_float() -> usize {
+                0 #( + <#types as #krate::Pack>::size_float() )*
+            }
+            fn pack_to(&self, buffer_int: &mut [i32], buffer_float: &mut [f32]) {
+                #krate::pack::Packer::new(buffer_int, buffer_float)
+                #( .pack(&#fields) )*;
+            }
+            fn layout() -> Option<#krate::layout::Layout> {
+                use #krate::layout::{Layout, FieldsBuilder};
+                let fields = FieldsBuilder::new()
+                #( .field::<#types>(#names) )*
+                .build();
+                Some(Layout::structure::<Self>(#name, #prefix, fields))
+            }
+        }
+    ))
+}
+
+fn unpack_impl(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let krate = crate_path(input)?;
+    // `Unpacker::unpack` also requires `Pack` to skip the data of the field.
+    let header = impl_header(
+        input,
+        quote!(#krate::Unpack),
+        quote!(#krate::Pack + #krate::Unpack),
+    )?;
+    let fields = match &input.data {
+        Data::Struct(data) => &data.fields,
+        _ => return Err(Error::new_spanned(&input.ident, "only structs are supported")),
+    };
+    let unpack = quote!(unpacker.unpack()?);
+    let construct = match fields {
+        Fields::Named(named) => {
+            let names = named.named.iter().map(|f| f.ident.as_ref().unwrap());
+            quote!(Self { #( #names: #unpack, )* })
+        },
+        Fields::Unnamed(unnamed) => {
+            let unpacks = unnamed.unnamed.iter().map(|_| &unpack);
+            quote!(Self( #( #unpacks, )* ))
+        },
+        Fields::Unit => quote!(Self),
+    };
+    Ok(quote!(
+        #header {
+            #[allow(unused_mut, unused_variables)]
+            fn unpack_from(buffer_int: &[i32], buffer_float: &[f32]) -> #krate::Result<Self> {
+                let mut unpacker = #krate::pack::Unpacker::new(buffer_int, buffer_float);
+                Ok(#construct)
+            }
+        }
+    ))
+}
+
+/// Parsed `#[instance(class = "...", source = "...", name = "...")]` attribute.
+struct InstanceAttr {
+    class: Path,
+    source: LitStr,
+    name: Option<LitStr>,
+}
+
+fn instance_attrs(input: &DeriveInput) -> syn::Result<Vec<InstanceAttr>> {
+    let mut attrs = Vec::new();
+    for attr in input.attrs.iter().filter(|a| a.path().is_ident("instance")) {
+        let (mut class, mut source, mut name) = (None, None, None);
+        attr.parse_nested_meta(|meta| {
+            let value: LitStr = meta.value()?.parse()?;
+            if meta.path.is_ident("class") {
+                class = Some(value.parse::<Path>()?);
+            } else if meta.path.is_ident("source") {
+                source = Some(value);
+            } else if meta.path.is_ident("name") {
+                name = Some(value);
+            } else {
+                return Err(meta.error("unknown attribute, expected `class`, `source` or `name`"));
+            }
+            Ok(())
+        })?;
+        attrs.push(InstanceAttr {
+            class: class.ok_or_else(|| Error::new_spanned(attr, "missing `class` attribute"))?,
+            source: source.ok_or_else(|| Error::new_spanned(attr, "missing `source` attribute"))?,
+            name,
+        });
+    }
+    if attrs.is_empty() {
+        return Err(Error::new_spanned(
+            &input.ident,
+            "`#[instance(class = \"...\", source = \"...\")]` attribute is required",
+        ));
+    }
+    Ok(attrs)
+}
+
+/// Converts `CamelCase` identifier to `snake_case`.
+fn snake_case(ident: &Ident) -> String {
+    let mut name = String::new();
+    for (i, c) in ident.to_string().chars().enumerate() {
+        if c.is_uppercase() {
+            if i > 0 {
+                name.push('_');
+            }
+            name.extend(c.to_lowercase());
+        } else {
+            name.push(c);
+        }
+    }
+    name
+}
+
+fn instance_impl(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let ident = &input.ident;
+    let krate = crate_path(input)?;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let impls = instance_attrs(input)?.into_iter().map(|attr| {
+        let class = attr.class;
+        let include = format!("#include <{}>", attr.source.value());
+        let name = attr.name.unwrap_or_else(|| LitStr::new(&snake_case(ident), Span::call_site()));
+        quote!(
+            impl #impl_generics #krate::class::Instance<#class> for #ident #ty_generics #where_clause {
+                fn source(_: &mut ::std::collections::HashSet<u64>) -> String {
+                    [
+                        #krate::layout::layout_source::<Self>(),
+                        #include.to_string(),
+                    ].join("\n")
+                }
+                fn inst_name() -> String {
+                    #name.to_string()
+                }
+            }
+        )
+    }).collect::<Vec<_>>();
+    Ok(quote!( #( #impls )* ))
+}
+
+fn expand(input: TokenStream, f: fn(&DeriveInput) -> syn::Result<TokenStream2>) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    f(&input).unwrap_or_else(|e| e.to_compile_error()).into()
+}
+
+/// Derives `clay_core::Pack` by packing struct fields one after another.
+///
+/// The layout of fields is also described, so OpenCL structure, `<type>_load` function
+/// and `<TYPE>_<FIELD>_DI`/`<TYPE>_<FIELD>_DF` offset macros are generated for the type.
+#[proc_macro_derive(Pack, attributes(pack))]
+pub fn derive_pack(input: TokenStream) -> TokenStream {
+    expand(input, pack_impl)
+}
+
+/// Derives `clay_core::Unpack` by unpacking struct fields one after another.
+#[proc_macro_derive(Unpack, attributes(pack))]
+pub fn derive_unpack(input: TokenStream) -> TokenStream {
+    expand(input, unpack_impl)
+}
+
+/// Derives `clay_core::Instance` for classes specified in `#[instance(...)]` attributes.
+///
+/// The type must also implement `clay_core::Pack`, its layout definitions
+/// are put before the included OpenCL source.
+#[proc_macro_derive(Instance, attributes(instance, pack))]
+pub fn derive_instance(input: TokenStream) -> TokenStream {
+    expand(input, instance_impl)
+}
+
+#[cfg(test)]
+mod test {
+    use syn::parse_quote;
+    use super::*;
+
+    fn error_str(input: DeriveInput, f: fn(&DeriveInput) -> syn::Result<TokenStream2>) -> String {
+        f(&input).unwrap_err().to_string()
+    }
+
+    #[test]
+    fn errors() {
+        let msg = error_str(parse_quote!(enum Bad { A, B }), pack_impl);
+        assert!(msg.contains("only structs are supported"));
+        let msg = error_str(parse_quote!(struct NoAttr {}), instance_impl);
+        assert!(msg.contains("attribute is required"));
+        let msg = error_str(parse_quote!(
+            #[instance(class = "MapClass")]
+            struct NoSource {}
+        ), instance_impl);
+        assert!(msg.contains("missing `source` attribute"));
+        let msg = error_str(parse_quote!(struct Bad { #[pack(rename = "x")] a: f64 }), pack_impl);
+        assert!(msg.contains("expected `name`"));
+        let msg = error_str(parse_quote!(#[pack(path = "x")] struct Bad { a: f64 }), pack_impl);
+        assert!(msg.contains("expected `crate`"));
+    }
+}
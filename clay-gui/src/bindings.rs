@@ -0,0 +1,250 @@
+use std::{
+    fs,
+    path::Path,
+};
+use sdl2::{keyboard::Keycode, mouse::MouseButton};
+use crate::EDIT_KEYS;
+
+
+/// Camera control action.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Action {
+    Forward,
+    Backward,
+    Left,
+    Right,
+    Up,
+    Down,
+    RollLeft,
+    RollRight,
+}
+
+impl Action {
+    pub fn all() -> [Action; 8] {
+        [
+            Action::Forward, Action::Backward,
+            Action::Left, Action::Right,
+            Action::Up, Action::Down,
+            Action::RollLeft, Action::RollRight,
+        ]
+    }
+
+    /// Name of the action in the bindings config.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Action::Forward => "forward",
+            Action::Backward => "backward",
+            Action::Left => "left",
+            Action::Right => "right",
+            Action::Up => "up",
+            Action::Down => "down",
+            Action::RollLeft => "roll_left",
+            Action::RollRight => "roll_right",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Action> {
+        Self::all().iter().cloned().find(|a| a.name() == name)
+    }
+}
+
+/// Action of the mouse scroll wheel.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WheelAction {
+    /// Multiplies the movement speed by `scroll_factor` per step.
+    Speed,
+    /// Moves the camera forward or backward, the orbiting camera approaches its target.
+    Move,
+    None,
+}
+
+impl WheelAction {
+    pub fn all() -> [WheelAction; 3] {
+        [WheelAction::Speed, WheelAction::Move, WheelAction::None]
+    }
+
+    /// Name of the action in the bindings config.
+    pub fn name(&self) -> &'static str {
+        match self {
+            WheelAction::Speed => "speed",
+            WheelAction::Move => "move",
+            WheelAction::None => "none",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<WheelAction> {
+        Self::all().iter().cloned().find(|a| a.name() == name)
+    }
+}
+
+/// Parses SDL mouse button name: `Left`, `Middle`, `Right`, `X1` or `X2`.
+pub fn mouse_button_from_name(name: &str) -> Option<MouseButton> {
+    match name {
+        "Left" => Some(MouseButton::Left),
+        "Middle" => Some(MouseButton::Middle),
+        "Right" => Some(MouseButton::Right),
+        "X1" => Some(MouseButton::X1),
+        "X2" => Some(MouseButton::X2),
+        _ => None,
+    }
+}
+
+/// Input bindings of the camera controls.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Bindings {
+    pub keys: Vec<(Keycode, Action)>,
+    /// Mouse button held to rotate the camera by dragging when the mouse is not captured,
+    /// the first-person camera looks around and the orbiting one orbits its target.
+    pub look_button: MouseButton,
+    /// Mouse button that picks an object.
+    pub pick_button: MouseButton,
+    pub wheel: WheelAction,
+    /// Inverts vertical mouse motion.
+    pub invert_y: bool,
+    /// Camera rotation in radians per pixel of mouse motion.
+    pub mouse_sensitivity: f64,
+    /// Movement speed multiplier per scroll wheel step.
+    pub scroll_factor: f64,
+}
+
+impl Default for Bindings {
+    fn default() -> Self {
+        Self {
+            keys: vec![
+                (Keycode::W, Action::Forward),
+                (Keycode::Up, Action::Forward),
+                (Keycode::S, Action::Backward),
+                (Keycode::Down, Action::Backward),
+                (Keycode::A, Action::Left),
+                (Keycode::Left, Action::Left),
+                (Keycode::D, Action::Right),
+                (Keycode::Right, Action::Right),
+                (Keycode::Space, Action::Up),
+                (Keycode::LShift, Action::Down),
+                (Keycode::Q, Action::RollLeft),
+                (Keycode::E, Action::RollRight),
+            ],
+            look_button: MouseButton::Left,
+            pick_button: MouseButton::Right,
+            wheel: WheelAction::Speed,
+            invert_y: false,
+            mouse_sensitivity: 4e-3,
+            scroll_factor: 1.25,
+        }
+    }
+}
+
+impl Bindings {
+    pub fn action(&self, key: Keycode) -> Option<Action> {
+        self.keys.iter().find(|(k, _)| *k == key).map(|(_, a)| *a)
+    }
+
+    /// Parses the bindings config.
+    ///
+    /// Each line has `name = value` form, `#` starts a comment. The names are
+    /// `mouse_sensitivity`, `scroll_factor`, `invert_y` (`true` or `false`),
+    /// `look_button` and `pick_button` (mouse button names), `wheel` (`speed`, `move` or `none`)
+    /// or action names, the value of an action is a comma-separated list of SDL key names.
+    /// Actions that are present in the config replace the default bindings, the rest are kept.
+    /// The keys editing the selected object (`EDIT_KEYS`) could not be bound.
+    pub fn parse(text: &str) -> clay_core::Result<Self> {
+        let mut bindings = Self::default();
+        for (i, line) in text.lines().enumerate() {
+            let line = line.split('#').next().unwrap().trim();
+            if line.is_empty() {
+                continue;
+            }
+            let error = |msg: String| format!("bindings line {}: {}", i + 1, msg);
+            let mut parts = line.splitn(2, '=');
+            let name = parts.next().unwrap().trim();
+            let value = parts.next().ok_or_else(|| error("expected '='".to_string()))?.trim();
+            match name {
+                "mouse_sensitivity" | "scroll_factor" => {
+                    let x = value.parse::<f64>().map_err(|e| error(e.to_string()))?;
+                    if name == "scroll_factor" {
+                        bindings.scroll_factor = x;
+                    } else {
+                        bindings.mouse_sensitivity = x;
+                    }
+                },
+                "invert_y" => {
+                    bindings.invert_y = value.parse::<bool>().map_err(|e| error(e.to_string()))?;
+                },
+                "look_button" | "pick_button" => {
+                    let button = mouse_button_from_name(value)
+                    .ok_or_else(|| error(format!("unknown mouse button '{}'", value)))?;
+                    if name == "look_button" {
+                        bindings.look_button = button;
+                    } else {
+                        bindings.pick_button = button;
+                    }
+                },
+                "wheel" => {
+                    bindings.wheel = WheelAction::from_name(value)
+                    .ok_or_else(|| error(format!("unknown wheel action '{}'", value)))?;
+                },
+                _ => {
+                    let action = Action::from_name(name)
+                    .ok_or_else(|| error(format!("unknown action '{}'", name)))?;
+                    bindings.keys.retain(|(_, a)| *a != action);
+                    for key_name in value.split(',').map(|k| k.trim()).filter(|k| !k.is_empty()) {
+                        let key = Keycode::from_name(key_name)
+                        .ok_or_else(|| error(format!("unknown key '{}'", key_name)))?;
+                        if EDIT_KEYS.contains(&key) {
+                            return Err(error(format!("key '{}' is used to edit the selected object", key_name)).into());
+                        }
+                        bindings.keys.push((key, action));
+                    }
+                },
+            }
+        }
+        Ok(bindings)
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> clay_core::Result<Self> {
+        Self::parse(&fs::read_to_string(path)?)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parse() {
+        let bindings = Bindings::parse("
+            # disable forward movement
+            forward =
+            scroll_factor = 2.0
+        ").unwrap();
+        assert_eq!(bindings.action(Keycode::W), None);
+        assert_eq!(bindings.action(Keycode::A), Some(Action::Left));
+        assert_eq!(bindings.scroll_factor, 2.0);
+
+        assert!(Bindings::parse("jump = Space").is_err());
+        assert!(Bindings::parse("forward").is_err());
+        assert!(Bindings::parse("scroll_factor = fast").is_err());
+        // Editing keys are reserved
+        assert!(Bindings::parse("forward = I").is_err());
+        assert!(Bindings::parse("roll_left = Q, R").is_err());
+    }
+
+    #[test]
+    fn parse_mouse() {
+        let bindings = Bindings::parse("
+            look_button = Middle
+            pick_button = Left # pick by left click
+            wheel = move
+            invert_y = true
+        ").unwrap();
+        assert_eq!(bindings.look_button, MouseButton::Middle);
+        assert_eq!(bindings.pick_button, MouseButton::Left);
+        assert_eq!(bindings.wheel, WheelAction::Move);
+        assert!(bindings.invert_y);
+        assert_eq!(Bindings::parse("").unwrap().wheel, WheelAction::Speed);
+
+        assert!(Bindings::parse("look_button = Wheel").is_err());
+        assert!(Bindings::parse("wheel = zoom").is_err());
+        assert!(Bindings::parse("invert_y = yes").is_err());
+    }
+}
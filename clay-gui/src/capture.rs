@@ -0,0 +1,109 @@
+use std::{
+    fs::{self, File},
+    io::{Write, BufWriter},
+    path::{Path, PathBuf},
+    process::{Command, Child, Stdio},
+};
+use nalgebra::{Vector3, Rotation3};
+use clay_core::export::{save_ppm, write_ppm};
+
+
+/// Returns the first nonexistent path `dir/<prefix>_<n><suffix>`.
+pub fn unique_path(dir: &Path, prefix: &str, suffix: &str) -> PathBuf {
+    (0..).map(|i| dir.join(format!("{}_{:04}{}", prefix, i, suffix)))
+    .find(|p| !p.exists()).unwrap()
+}
+
+/// Saves the image to a new PPM file in the directory and returns its path.
+pub fn screenshot(dir: &Path, dims: (usize, usize), data: &[u8]) -> clay_core::Result<PathBuf> {
+    fs::create_dir_all(dir)?;
+    let path = unique_path(dir, "screenshot", ".ppm");
+    save_ppm(&path, dims, data)?;
+    Ok(path)
+}
+
+enum Sink {
+    Directory(PathBuf),
+    Process(Child),
+}
+
+/// Records the sequence of frames and the camera path.
+///
+/// Frames are written as PPM files to the recording directory or piped as a PPM stream
+/// to stdin of the external command (e.g. `ffmpeg -f image2pipe -i - out.mp4`).
+/// The camera path is written to `camera.csv` in the recording directory.
+pub struct Recorder {
+    dir: PathBuf,
+    sink: Sink,
+    camera: BufWriter<File>,
+    frames: usize,
+}
+
+impl Recorder {
+    /// Starts recording to a new subdirectory of `dir`.
+    pub fn start(dir: &Path, command: Option<&str>) -> clay_core::Result<Self> {
+        fs::create_dir_all(dir)?;
+        let dir = unique_path(dir, "record", "");
+        fs::create_dir(&dir)?;
+        let sink = match command {
+            Some(command) => Sink::Process(
+                Command::new("sh").arg("-c").arg(command)
+                .current_dir(&dir)
+                .stdin(Stdio::piped())
+                .spawn()?
+            ),
+            None => Sink::Directory(dir.clone()),
+        };
+        let mut camera = BufWriter::new(File::create(dir.join("camera.csv"))?);
+        writeln!(camera, "frame,x,y,z,m00,m01,m02,m10,m11,m12,m20,m21,m22")?;
+        Ok(Self { dir, sink, camera, frames: 0 })
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+    /// Number of recorded frames.
+    pub fn frames(&self) -> usize {
+        self.frames
+    }
+
+    pub fn record(
+        &mut self,
+        dims: (usize, usize),
+        data: &[u8],
+        pos: Vector3<f64>,
+        map: Rotation3<f64>,
+    ) -> clay_core::Result<()> {
+        match &mut self.sink {
+            Sink::Directory(dir) => {
+                save_ppm(dir.join(format!("frame_{:06}.ppm", self.frames)), dims, data)?;
+            },
+            Sink::Process(child) => {
+                let stdin = child.stdin.as_mut().ok_or("encoder stdin is closed")?;
+                write_ppm(stdin, dims, data)?;
+            },
+        }
+        let m = map.matrix();
+        let values = [pos.x, pos.y, pos.z]
+        .iter().chain(m.transpose().iter())
+        .map(|x| x.to_string())
+        .collect::<Vec<_>>();
+        writeln!(self.camera, "{},{}", self.frames, values.join(","))?;
+        self.frames += 1;
+        Ok(())
+    }
+
+    /// Finishes recording and waits for the external command to exit.
+    pub fn finish(mut self) -> clay_core::Result<()> {
+        self.camera.flush()?;
+        if let Sink::Process(mut child) = self.sink {
+            // Closing stdin signals the end of the stream
+            drop(child.stdin.take());
+            let status = child.wait()?;
+            if !status.success() {
+                return Err(format!("encoder exited with {}", status).into());
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,167 @@
+use std::f64::consts::PI;
+use nalgebra::{Vector3, Rotation3};
+
+
+/// Roll speed in radians per second.
+const ROLL_SPEED: f64 = 1.0;
+
+/// Camera movement requested by the user during the time step.
+#[derive(Clone, Debug)]
+pub struct Movement {
+    /// Direction in camera space (`x` to the right, `y` up, `z` backward), normalized or zero.
+    pub dir: Vector3<f64>,
+    /// Vertical movement in world space: `1` is up, `-1` is down.
+    pub lift: f64,
+    /// Roll direction: `1` is clockwise, `-1` is counter-clockwise.
+    pub roll: f64,
+    /// Movement speed in units per second.
+    pub speed: f64,
+    /// Time step in seconds.
+    pub dt: f64,
+}
+
+/// Camera controlled by the user input.
+pub trait Controller {
+    /// Rotates the camera by the mouse motion converted to radians.
+    fn rotate(&mut self, dx: f64, dy: f64);
+    /// Moves the camera.
+    fn step(&mut self, movement: &Movement);
+
+    fn position(&self) -> Vector3<f64>;
+    /// Rotation from camera space to world space.
+    fn orientation(&self) -> Rotation3<f64>;
+}
+
+fn look(phi: f64, theta: f64, roll: f64) -> Rotation3<f64> {
+    Rotation3::from_axis_angle(&(-Vector3::z_axis()), phi)*
+    Rotation3::from_axis_angle(&Vector3::x_axis(), theta)*
+    Rotation3::from_axis_angle(&(-Vector3::z_axis()), roll)
+}
+
+fn turn(phi: &mut f64, theta: &mut f64, dx: f64, dy: f64) {
+    *phi = (*phi + dx).rem_euclid(2.0*PI);
+    *theta = (*theta - dy).clamp(0.0, PI);
+}
+
+/// First-person camera that moves in the direction of view.
+#[derive(Clone, Debug)]
+pub struct FirstPerson {
+    pub pos: Vector3<f64>,
+    /// Azimuth angle.
+    pub phi: f64,
+    /// Polar angle, `0` is looking down.
+    pub theta: f64,
+    pub roll: f64,
+}
+
+impl Default for FirstPerson {
+    fn default() -> Self {
+        Self { pos: Vector3::zeros(), phi: 0.0, theta: 0.5*PI, roll: 0.0 }
+    }
+}
+
+impl Controller for FirstPerson {
+    fn rotate(&mut self, dx: f64, dy: f64) {
+        turn(&mut self.phi, &mut self.theta, dx, dy);
+    }
+    fn step(&mut self, m: &Movement) {
+        let dir = self.orientation()*m.dir + Vector3::new(0.0, 0.0, m.lift);
+        self.pos += m.speed*m.dt*dir;
+        self.roll += ROLL_SPEED*m.dt*m.roll;
+    }
+    fn position(&self) -> Vector3<f64> {
+        self.pos
+    }
+    fn orientation(&self) -> Rotation3<f64> {
+        look(self.phi, self.theta, self.roll)
+    }
+}
+
+/// Camera that orbits around the target point.
+///
+/// Mouse and side movement rotate the camera around the target,
+/// forward and backward movement change the distance to it.
+#[derive(Clone, Debug)]
+pub struct Orbit {
+    pub target: Vector3<f64>,
+    pub distance: f64,
+    pub phi: f64,
+    pub theta: f64,
+    pub roll: f64,
+}
+
+impl Orbit {
+    pub fn new(target: Vector3<f64>, distance: f64) -> Self {
+        Self { target, distance, phi: 0.0, theta: 0.5*PI, roll: 0.0 }
+    }
+}
+
+impl Controller for Orbit {
+    fn rotate(&mut self, dx: f64, dy: f64) {
+        turn(&mut self.phi, &mut self.theta, dx, dy);
+    }
+    fn step(&mut self, m: &Movement) {
+        let d = m.speed*m.dt;
+        self.distance = (self.distance + d*m.dir.z).max(1e-3);
+        turn(&mut self.phi, &mut self.theta, -d*m.dir.x/self.distance, 0.0);
+        self.target.z += d*m.lift;
+        self.roll += ROLL_SPEED*m.dt*m.roll;
+    }
+    fn position(&self) -> Vector3<f64> {
+        self.target + self.orientation()*Vector3::new(0.0, 0.0, self.distance)
+    }
+    fn orientation(&self) -> Rotation3<f64> {
+        look(self.phi, self.theta, self.roll)
+    }
+}
+
+/// Camera that freely rotates around the target point without a fixed up direction.
+///
+/// Side and vertical movement pan the target, forward and backward movement
+/// change the distance to it.
+#[derive(Clone, Debug)]
+pub struct Trackball {
+    pub target: Vector3<f64>,
+    pub distance: f64,
+    pub rotation: Rotation3<f64>,
+}
+
+impl Trackball {
+    pub fn new(target: Vector3<f64>, distance: f64) -> Self {
+        Self { target, distance, rotation: look(0.0, 0.5*PI, 0.0) }
+    }
+}
+
+impl Controller for Trackball {
+    fn rotate(&mut self, dx: f64, dy: f64) {
+        self.rotation *= Rotation3::new(Vector3::new(-dy, -dx, 0.0));
+    }
+    fn step(&mut self, m: &Movement) {
+        let d = m.speed*m.dt;
+        self.distance = (self.distance + d*m.dir.z).max(1e-3);
+        self.target += self.rotation*Vector3::new(d*m.dir.x, d*m.lift, 0.0);
+        self.rotation *= Rotation3::new(Vector3::new(0.0, 0.0, -ROLL_SPEED*m.dt*m.roll));
+    }
+    fn position(&self) -> Vector3<f64> {
+        self.target + self.rotation*Vector3::new(0.0, 0.0, self.distance)
+    }
+    fn orientation(&self) -> Rotation3<f64> {
+        self.rotation
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn orbit_looks_at_target() {
+        let target = Vector3::new(1.0, 2.0, 3.0);
+        let mut orbit = Orbit::new(target, 2.0);
+        orbit.rotate(0.3, -0.2);
+        let view = orbit.orientation()*Vector3::new(0.0, 0.0, -1.0);
+        let dir = (target - orbit.position()).normalize();
+        assert!((view - dir).norm() < 1e-9);
+        assert!(((target - orbit.position()).norm() - 2.0).abs() < 1e-9);
+    }
+}
@@ -1,20 +1,41 @@
 mod motion;
+mod controller;
+pub use controller::*;
+mod bindings;
+pub use bindings::*;
+mod capture;
+pub use capture::*;
+mod picking;
+pub use picking::*;
+pub mod overlay;
 
 use std::{
+    thread,
+    path::PathBuf,
     time::{Duration, Instant},
 };
 use sdl2::{
     self,
     Sdl, VideoSubsystem,
-    render::{WindowCanvas, TextureAccess},
+    render::{WindowCanvas, Texture, TextureCreator, TextureAccess},
+    video::{WindowContext, FullscreenType},
     pixels::PixelFormatEnum,
-    event::Event,
+    event::{Event, WindowEvent},
     keyboard::Keycode,
 };
 use nalgebra::{Vector3, Rotation3};
-use clay_core::{Context, Screen};
+use clay_core::{Context, Screen, RenderHandle, Aov, Denoise, Track, Camera, Interpolation};
 use motion::Motion;
 
+/// Render settings controlled from the window.
+#[derive(Clone, Debug, Default)]
+pub struct Settings {
+    /// Denoiser settings, toggled by `N` key, its strength is changed by `[` and `]` keys.
+    pub denoise: Denoise,
+    /// Object picking state, the pick and the edits are applied by `Picking::apply`.
+    pub picking: Picking,
+}
+
 #[allow(dead_code)]
 pub struct Window {
     context: Sdl,
@@ -22,15 +43,28 @@ pub struct Window {
     size: (usize, usize),
     canvas: WindowCanvas,
     capture: bool,
+    motion: Motion,
+    render_scale: f64,
+    overlay: bool,
+    capture_dir: PathBuf,
+    record_command: Option<String>,
+    aovs: Vec<Aov>,
+    settings: Settings,
+    track: Track<Camera>,
+    /// Last status message and the time it was shown at.
+    message: Option<(String, Instant)>,
 }
 
+/// Time the status message is shown in the overlay.
+const MESSAGE_TIME: Duration = Duration::from_secs(3);
+
 impl Window {
     pub fn new(size: (usize, usize)) -> clay_core::Result<Self> {
         let context = sdl2::init()?;
         let video = context.video()?;
      
         let window = video.window("Clay", size.0 as u32, size.1 as u32)
-        .position_centered()/*.resizable()*/.build()
+        .position_centered().resizable().build()
         .map_err(|e| e.to_string())?;
      
         let canvas = window.into_canvas().build().map_err(|e| e.to_string())?;
@@ -41,6 +75,15 @@ impl Window {
             context, video,
             size, canvas,
             capture: false,
+            motion: Motion::new(),
+            render_scale: 1.0,
+            overlay: true,
+            capture_dir: PathBuf::from("."),
+            record_command: None,
+            aovs: Vec::new(),
+            settings: Settings::default(),
+            track: Track::new(Interpolation::CatmullRom),
+            message: None,
         };
 
         self_.toggle_capture();
@@ -53,95 +96,361 @@ impl Window {
         self.context.mouse().set_relative_mouse_mode(self.capture);
     }
 
-    pub fn start<F>(&mut self, context: &Context, mut render: F) -> clay_core::Result<()>
-    where F: FnMut(&mut Screen, Vector3<f64>, Rotation3<f64>) -> clay_core::Result<()> {
-        let mut screen = Screen::new(context, self.size).map_err(|e| e.to_string())?;
+    /// Current size of the window.
+    pub fn size(&self) -> (usize, usize) {
+        self.size
+    }
 
-        let texture_creator = self.canvas.texture_creator();
-        let mut texture = texture_creator.create_texture(
+    /// Sets the ratio of the screen resolution to the window size.
+    ///
+    /// The values less than `1.0` make rendering faster, the image is upscaled to the window size.
+    pub fn render_scale(&mut self, scale: f64) -> &mut Self {
+        assert!(scale > 0.0, "render scale must be positive");
+        self.render_scale = scale;
+        self
+    }
+
+    fn screen_dims(&self) -> (usize, usize) {
+        let scale = |x: usize| ((x as f64*self.render_scale).round() as usize).max(1);
+        (scale(self.size.0), scale(self.size.1))
+    }
+
+    fn toggle_fullscreen(&mut self) -> clay_core::Result<()> {
+        let window = self.canvas.window_mut();
+        let mode = match window.fullscreen_state() {
+            FullscreenType::Off => FullscreenType::Desktop,
+            _ => FullscreenType::Off,
+        };
+        window.set_fullscreen(mode)?;
+        Ok(())
+    }
+
+    fn create_screen<'a>(
+        &self,
+        context: &Context,
+        texture_creator: &'a TextureCreator<WindowContext>,
+    ) -> clay_core::Result<(Screen, Texture<'a>)> {
+        let dims = self.screen_dims();
+        let screen = Screen::with_aovs(context, dims, &self.aovs)?;
+        let texture = texture_creator.create_texture(
             PixelFormatEnum::RGB24,
             TextureAccess::Streaming,
-            self.size.0 as u32,
-            self.size.1 as u32,
+            dims.0 as u32,
+            dims.1 as u32,
         )
         .map_err(|e| e.to_string())?;
+        Ok((screen, texture))
+    }
+
+    /// Sets the camera controller, the first-person camera at the origin is used by default.
+    pub fn controller<C: Controller + 'static>(&mut self, controller: C) -> &mut Self {
+        self.motion.controller = Box::new(controller);
+        self
+    }
+
+    /// Sets the input bindings of camera controls.
+    pub fn bindings(&mut self, bindings: Bindings) -> &mut Self {
+        self.motion.bindings = bindings;
+        self
+    }
+
+    /// Sets the directory for screenshots (`F12` key), recordings (`F10` key) and camera tracks (`F6` key).
+    pub fn capture_dir<P: Into<PathBuf>>(&mut self, dir: P) -> &mut Self {
+        self.capture_dir = dir.into();
+        self
+    }
+
+    /// Sets the shell command that receives recorded frames as a PPM stream on its stdin,
+    /// `None` saves frames as separate files.
+    pub fn record_command(&mut self, command: Option<&str>) -> &mut Self {
+        self.record_command = command.map(String::from);
+        self
+    }
+
+    /// Shows the status overlay, it is toggled by `F1` key.
+    pub fn overlay(&mut self, enabled: bool) -> &mut Self {
+        self.overlay = enabled;
+        self
+    }
+
+    /// Lines of the status overlay.
+    fn status(&self, fps: f64, passes: usize, dims: (usize, usize), recorder: &Option<Recorder>) -> Vec<String> {
+        let (pos, map) = (self.motion.pos(), self.motion.map());
+        let dir = map*Vector3::new(0.0, 0.0, -1.0);
+        let denoise = &self.settings.denoise;
+        let mut lines = vec![
+            format!("fps: {:.1}", fps.max(0.0)),
+            format!("passes: {}", passes),
+            format!("pos: {:.2} {:.2} {:.2}", pos.x, pos.y, pos.z),
+            format!("dir: {:.2} {:.2} {:.2}", dir.x, dir.y, dir.z),
+            format!("speed: {:.2}", self.motion.speed),
+            format!(
+                "screen: {}x{} scale {:.2}",
+                dims.0, dims.1, self.render_scale,
+            ),
+            if denoise.enabled {
+                format!("denoise: on {:.3}", denoise.strength)
+            } else {
+                "denoise: off".to_string()
+            },
+        ];
+        if let Some(i) = self.settings.picking.selected {
+            lines.push(format!("selected: {}", i));
+        }
+        if let Some(r) = recorder {
+            lines.push(format!("rec: {} frames", r.frames()));
+        }
+        if !self.track.keys().is_empty() {
+            lines.push(format!("keys: {}", self.track.keys().len()));
+        }
+        if let Some((message, _)) = &self.message {
+            lines.push(message.clone());
+        }
+        lines
+    }
+
+    /// Shows the message in the overlay for a few seconds.
+    fn notify(&mut self, message: String) {
+        self.message = Some((message, Instant::now()));
+    }
+
+    /// Camera track recorded in the window.
+    ///
+    /// `F5` key adds the current camera as a keyframe one second after the last one,
+    /// `F6` saves the track to the capture directory and `F7` clears it.
+    pub fn track(&self) -> &Track<Camera> {
+        &self.track
+    }
+    pub fn track_mut(&mut self) -> &mut Track<Camera> {
+        &mut self.track
+    }
+
+    /// Edits the camera track, the number of keyframes is shown in the overlay.
+    fn handle_track(&mut self, key: Keycode) -> clay_core::Result<()> {
+        match key {
+            Keycode::F5 => {
+                let time = self.track.range().map_or(0.0, |(_, last)| last + 1.0);
+                self.track.add(time, Camera::new(self.motion.pos(), self.motion.map()));
+                self.notify(format!("keyframe at {} s", time));
+            },
+            Keycode::F6 => {
+                std::fs::create_dir_all(&self.capture_dir)?;
+                let path = unique_path(&self.capture_dir, "camera", ".track");
+                self.track.save(&path)?;
+                self.notify(format!("saved {}", path.display()));
+            },
+            Keycode::F7 => {
+                self.track.clear();
+                self.notify("track cleared".to_string());
+            },
+            _ => (),
+        }
+        Ok(())
+    }
+
+    /// Starts or stops recording, the frame count is shown in the overlay while recording.
+    fn toggle_recording(&mut self, recorder: &mut Option<Recorder>) -> clay_core::Result<()> {
+        match recorder.take() {
+            Some(r) => {
+                self.notify(format!("recorded {} frames to {}", r.frames(), r.dir().display()));
+                r.finish()?;
+            },
+            None => {
+                let r = Recorder::start(&self.capture_dir, self.record_command.as_deref())?;
+                self.notify(format!("recording to {}", r.dir().display()));
+                *recorder = Some(r);
+            },
+        }
+        Ok(())
+    }
+
+    /// Sets the AOVs of the screen, they must match the AOVs of the worker.
+    pub fn aovs(&mut self, aovs: &[Aov]) -> &mut Self {
+        self.aovs = aovs.to_vec();
+        self
+    }
+
+    pub fn settings(&self) -> &Settings {
+        &self.settings
+    }
+    pub fn settings_mut(&mut self) -> &mut Settings {
+        &mut self.settings
+    }
+
+    /// Changes the denoiser settings, their state is shown in the overlay.
+    fn handle_settings(&mut self, key: Keycode) {
+        let denoise = &mut self.settings.denoise;
+        match key {
+            Keycode::N => denoise.enabled = !denoise.enabled,
+            Keycode::LeftBracket => denoise.strength *= 0.5,
+            Keycode::RightBracket => denoise.strength *= 2.0,
+            _ => (),
+        }
+    }
+
+    /// Runs the window loop.
+    ///
+    /// The `render` function should enqueue a rendering pass and return its handle,
+    /// the events are handled while the device is busy.
+    ///
+    /// The selected object is highlighted if the screen has `ObjectId` AOV.
+    pub fn start<F>(&mut self, context: &Context, mut render: F) -> clay_core::Result<()>
+    where F: FnMut(&mut Screen, Vector3<f64>, Rotation3<f64>, &mut Settings) -> clay_core::Result<RenderHandle> {
+        let texture_creator = self.canvas.texture_creator();
+        let (mut screen, mut texture) = self.create_screen(context, &texture_creator)?;
 
-        let mut motion = Motion::new();
         let mut drop_mouse = true;
         let instant = Instant::now();
         let mut prev = instant.elapsed();
         let mut fps = -1.0;
-        let mut printed = instant.elapsed();
+        let mut handle: Option<RenderHandle> = None;
+        let mut n_passes = 0;
+        let mut take_screenshot = false;
+        let mut recorder: Option<Recorder> = None;
 
         let mut event_pump = self.context.event_pump()?;
         'main: loop {
+            let mut resized = false;
             for event in event_pump.poll_iter() {
                 match event {
                     Event::Quit {..} => break 'main,
-                    Event::KeyDown { keycode: Some(key), .. } => match key {
-                        Keycode::Escape => break 'main,
-                        Keycode::Tab => {
-                            self.toggle_capture();
-                            if self.capture {
-                                drop_mouse = true;
-                            }
-                        },
-                        _ => (),
+                    Event::Window { win_event: WindowEvent::SizeChanged(w, h), .. } => {
+                        self.size = (w.max(1) as usize, h.max(1) as usize);
+                        resized = true;
+                    },
+                    Event::MouseButtonDown { mouse_btn, x, y, .. }
+                    if mouse_btn == self.motion.bindings.pick_button => {
+                        // The cursor is hidden in capture mode, so the screen center is picked
+                        let (sx, sy) = self.screen_dims();
+                        let pixel = if self.capture {
+                            (sx/2, sy/2)
+                        } else {
+                            let scale = |p: i32, s: usize, w: usize| (p.max(0) as usize*s/w).min(s - 1);
+                            (scale(x, sx, self.size.0), scale(y, sy, self.size.1))
+                        };
+                        self.settings.picking.cursor = Some(pixel);
+                    },
+                    Event::KeyDown { keycode: Some(key), keymod, .. } => {
+                        // Keys editing the selected object are not passed further
+                        let step = 0.1*self.motion.speed;
+                        if self.settings.picking.handle_key(key, keymod, &self.motion.map(), step) {
+                            continue;
+                        }
+                        match key {
+                            Keycode::Escape => break 'main,
+                            Keycode::F11 => self.toggle_fullscreen()?,
+                            Keycode::F12 => take_screenshot = true,
+                            Keycode::F10 => self.toggle_recording(&mut recorder)?,
+                            Keycode::F1 => self.overlay = !self.overlay,
+                            Keycode::F5 | Keycode::F6 | Keycode::F7 => self.handle_track(key)?,
+                            Keycode::Tab => {
+                                self.toggle_capture();
+                                if self.capture {
+                                    drop_mouse = true;
+                                }
+                            },
+                            key => self.handle_settings(key),
+                        }
                     },
                     _ => (),
                 }
-                motion.handle_keys(&event);
+                self.motion.handle_event(&event);
             }
             let rms = event_pump.relative_mouse_state();
             if self.capture {
                 if !drop_mouse {
-                    motion.handle_mouse(&rms);
+                    self.motion.handle_mouse(&rms);
                 } else {
                     drop_mouse = false;
                 }
-            } else if event_pump.mouse_state().left() {
-                motion.handle_mouse(&rms);
+            } else if event_pump.mouse_state().is_mouse_button_pressed(self.motion.bindings.look_button) {
+                self.motion.handle_mouse(&rms);
             }
 
-            if motion.updated || motion.key_mask != 0 {
-                screen.clear()?;
-                motion.updated = false;
+            if resized {
+                // The screen must not be released while the device renders to it
+                if let Some(h) = handle.take() {
+                    h.wait()?;
+                }
+                let (s, t) = self.create_screen(context, &texture_creator)?;
+                screen = s;
+                texture = t;
+                n_passes = 0;
             }
-            render(&mut screen, motion.pos, motion.map())?;
-            let mut n_passes = 1;
-            while instant.elapsed() - prev < Duration::from_millis(20) {
-                render(&mut screen, motion.pos, motion.map())?;
+
+            if let Some(h) = handle.take() {
+                if !h.is_complete()? {
+                    handle = Some(h);
+                    thread::sleep(Duration::from_millis(1));
+                    continue;
+                }
                 n_passes += 1;
             }
-            let data = screen.read()?;
 
-            texture.update(None, &data, 3*(screen.dims().0 as usize))
-            .map_err(|e| e.to_string())?;
+            let frame_done = n_passes > 0 && instant.elapsed() - prev >= Duration::from_millis(20);
+            let passes = screen.n_passes();
+            if frame_done {
+                let now = instant.elapsed();
 
-            //self.canvas.clear();
-            self.canvas.copy(&texture, None, None)?;
-            self.canvas.present();
+                let dt = now - prev;
+                self.motion.step(dt);
+                prev = now;
 
-            //thread::sleep(Duration::from_millis(20));
-            
-            let now = instant.elapsed();
+                let cfps = (n_passes as f64)*1e6/(dt.as_micros() as f64);
+                if fps < 0.0 {
+                    fps = cfps;
+                } else {
+                    fps = 0.95*fps + 0.05*cfps;
+                }
+                n_passes = 0;
+            }
 
-            let dt = now - prev;
-            motion.step(dt);
-            prev = now;
+            // Accumulated passes are dropped at the start of the frame if the view is changed,
+            // and immediately if the scene is going to be edited
+            let edited = !self.settings.picking.edits.is_empty();
+            if (n_passes == 0 && (self.motion.updated || self.motion.is_moving())) || edited {
+                screen.clear()?;
+                self.motion.updated = false;
+            }
+            handle = Some(render(&mut screen, self.motion.pos(), self.motion.map(), &mut self.settings)?);
+            if !frame_done {
+                continue;
+            }
 
-            let cfps = (n_passes as f64)*1e6/(dt.as_micros() as f64);
-            if fps < 0.0 {
-                fps = cfps;
-            } else {
-                fps = 0.95*fps + 0.05*cfps;
+            // The image of the completed pass is displayed while the device renders the next one
+            let mut data = screen.read_ready()?;
+            let dims = screen.dims();
+
+            if take_screenshot {
+                let path = screenshot(&self.capture_dir, dims, &data)?;
+                self.notify(format!("saved {}", path.display()));
+                take_screenshot = false;
             }
-            if (now - printed).as_secs() > 0 {
-                println!("FPS: {:.2}", fps);
-                printed = now;
+            if let Some(r) = &mut recorder {
+                r.record(dims, &data, self.motion.pos(), self.motion.map())?;
             }
+            if let (Some(i), Some(_)) = (self.settings.picking.selected, screen.aov(Aov::ObjectId)) {
+                highlight(&mut data, &screen.read_aov(Aov::ObjectId)?, i);
+            }
+            if self.message.as_ref().is_some_and(|(_, shown)| shown.elapsed() > MESSAGE_TIME) {
+                self.message = None;
+            }
+            if self.overlay {
+                let scale = if dims.1 >= 480 { 2 } else { 1 };
+                overlay::draw_text(&mut data, dims, scale, &self.status(fps, passes, dims, &recorder));
+            }
+
+            texture.update(None, &data, 3*dims.0)
+            .map_err(|e| e.to_string())?;
+
+            //self.canvas.clear();
+            self.canvas.copy(&texture, None, None)?;
+            self.canvas.present();
         }
 
+        if let Some(r) = recorder {
+            r.finish()?;
+        }
         Ok(())
     }
 } 
@@ -1,138 +1,110 @@
-use std::{
-    time::Duration,
-    f64::consts::PI,
-
-};
+use std::time::Duration;
 use sdl2::{
     event::Event,
     mouse::RelativeMouseState,
     keyboard::Keycode,
 };
 use nalgebra::{Vector3, Rotation3};
+use crate::{Action, Bindings, WheelAction, Controller, FirstPerson, Movement};
 
-fn clamp(x: f64, a: f64, b: f64) -> f64 {
-    if x < a {
-        a
-    } else if x > b {
-        b
-    } else {
-        x
-    }
-}
 
-const KEYS: [Keycode; 12] = [
-    Keycode::W,
-    Keycode::Up,
-    Keycode::A,
-    Keycode::Left,
-    Keycode::S,
-    Keycode::Down,
-    Keycode::D,
-    Keycode::Right,
-    Keycode::Space,
-    Keycode::LShift,
-    Keycode::Q,
-    Keycode::E,
-];
+/// Movement time per scroll wheel step in seconds.
+const WHEEL_STEP: f64 = 0.1;
 
-fn key_idx(key: Keycode) -> Option<usize> {
-    KEYS.iter().position(|k| *k == key)
-}
-
-fn key_dir(key: Keycode) -> (Vector3<f64>, Vector3<f64>) {
-    match key {
-        Keycode::W | Keycode::Up => (Vector3::new(0.0, 0.0, -1.0), Vector3::zeros()),
-        Keycode::A | Keycode::Left => (Vector3::new(-1.0, 0.0, 0.0), Vector3::zeros()),
-        Keycode::S | Keycode::Down => (Vector3::new(0.0, 0.0, 1.0), Vector3::zeros()),
-        Keycode::D | Keycode::Right => (Vector3::new(1.0, 0.0, 0.0), Vector3::zeros()),
-        Keycode::Space => (Vector3::zeros(), Vector3::new(0.0, 0.0, 1.0)),
-        Keycode::LShift => (Vector3::zeros(), Vector3::new(0.0, 0.0, -1.0)),
-        _ => (Vector3::zeros(), Vector3::zeros()),
-    }
-}
 
+/// Translates user input into the camera controller motion.
 pub struct Motion {
     pub updated: bool,
-    pub key_mask: usize,
-    pub pos: Vector3<f64>,
-    pub phi: f64,
-    pub theta: f64,
+    pub bindings: Bindings,
+    pub controller: Box<dyn Controller>,
     pub speed: f64,
-    pub sens: f64,
+    pressed: Vec<Keycode>,
 }
 
 impl Motion {
     pub fn new() -> Self {
         Self {
-            updated: false, key_mask: 0,
-            pos: Vector3::zeros(), phi: 0.0, theta: 0.5*PI,
-            speed: 1.0, sens: 4e-3,
+            updated: false,
+            bindings: Bindings::default(),
+            controller: Box::new(FirstPerson::default()),
+            speed: 1.0,
+            pressed: Vec::new(),
         }
     }
 
-    pub fn handle_keys(&mut self, event: &Event) {
+    pub fn handle_event(&mut self, event: &Event) {
         match event {
-            Event::KeyDown { keycode: Some(key), .. } => if let Some(i) = key_idx(*key) {
-                self.key_mask |= 1 << i;
+            Event::KeyDown { keycode: Some(key), .. }
+            if self.bindings.action(*key).is_some() && !self.pressed.contains(key) => {
+                self.pressed.push(*key);
                 self.updated = true;
             },
-            Event::KeyUp { keycode: Some(key), .. } => if let Some(i) = key_idx(*key) {
-                self.key_mask &= !(1 << i);
+            Event::KeyUp { keycode: Some(key), .. } if self.pressed.contains(key) => {
+                self.pressed.retain(|k| k != key);
                 self.updated = true;
             },
+            Event::MouseWheel { y, .. } => match self.bindings.wheel {
+                WheelAction::Speed => self.speed *= self.bindings.scroll_factor.powi(*y),
+                WheelAction::Move => {
+                    self.controller.step(&Movement {
+                        dir: Vector3::new(0.0, 0.0, -(*y as f64).signum()),
+                        lift: 0.0, roll: 0.0,
+                        speed: self.speed, dt: WHEEL_STEP*(y.abs() as f64),
+                    });
+                    self.updated = true;
+                },
+                WheelAction::None => (),
+            },
             _ => (),
         }
     }
 
     pub fn handle_mouse(&mut self, mouse: &RelativeMouseState) {
         if mouse.x() != 0 || mouse.y() != 0 {
-            self.phi += self.sens*(mouse.x() as f64);
-            let t = (self.phi/(2.0*PI)).floor() as i32;
-            if t != 0 {
-                self.phi -= 2.0*PI*(t as f64);
-            }
-
-            self.theta -= self.sens*(mouse.y() as f64);
-            if self.theta < 0.0 {
-                self.theta = 0.0;
-            } else if self.theta > PI {
-                self.theta = PI;
-            }
+            let sens = self.bindings.mouse_sensitivity;
+            let dy = if self.bindings.invert_y { -mouse.y() } else { mouse.y() };
+            self.controller.rotate(sens*(mouse.x() as f64), sens*(dy as f64));
             self.updated = true;
         }
     }
 
-    pub fn map_theta(&self) -> Rotation3<f64> {
-        Rotation3::from_axis_angle(
-            &Vector3::x_axis(),
-            self.theta,
-        )
-    }
-    pub fn map_phi(&self) -> Rotation3<f64> {
-        Rotation3::from_axis_angle(
-            &(-Vector3::z_axis()),
-            self.phi,
-        )
+    /// Whether any movement key is pressed.
+    pub fn is_moving(&self) -> bool {
+        !self.pressed.is_empty()
     }
 
+    pub fn pos(&self) -> Vector3<f64> {
+        self.controller.position()
+    }
     pub fn map(&self) -> Rotation3<f64> {
-        self.map_phi()*self.map_theta()
+        self.controller.orientation()
     }
 
     pub fn step(&mut self, dt: Duration) {
-        let (mut dir, mut idir) = (Vector3::zeros(), Vector3::zeros());
-        for (i, k) in KEYS.iter().enumerate() {
-            if ((self.key_mask >> i) & 1) != 0 {
-                let (dv, di) = key_dir(*k);
-                dir += dv;
-                idir += di;
+        let mut movement = Movement {
+            dir: Vector3::zeros(), lift: 0.0, roll: 0.0,
+            speed: self.speed, dt: dt.as_secs_f64(),
+        };
+        for key in self.pressed.iter() {
+            match self.bindings.action(*key) {
+                Some(Action::Forward) => movement.dir.z -= 1.0,
+                Some(Action::Backward) => movement.dir.z += 1.0,
+                Some(Action::Left) => movement.dir.x -= 1.0,
+                Some(Action::Right) => movement.dir.x += 1.0,
+                Some(Action::Up) => movement.lift += 1.0,
+                Some(Action::Down) => movement.lift -= 1.0,
+                Some(Action::RollLeft) => movement.roll -= 1.0,
+                Some(Action::RollRight) => movement.roll += 1.0,
+                None => (),
             }
         }
-        dir = dir.map(|x| clamp(x, -1.0, 1.0));
-        if dir.norm() > 1e-4 {
-            dir = dir.normalize();
+        // Keys bound to the same action should not speed it up
+        movement.dir = movement.dir.map(|x| x.clamp(-1.0, 1.0));
+        if movement.dir.norm() > 1e-4 {
+            movement.dir = movement.dir.normalize();
         }
-        dir = self.map()*dir;
-        self.pos += 1e-6*(dt.as_micros() as f64)*self.speed*(dir + idir);
+        movement.lift = movement.lift.clamp(-1.0, 1.0);
+        movement.roll = movement.roll.clamp(-1.0, 1.0);
+        self.controller.step(&movement);
     }
 }
@@ -0,0 +1,104 @@
+/// Width and height of the font glyphs in pixels.
+const GLYPH: (usize, usize) = (3, 5);
+
+/// Rows of the glyph from top to bottom, the highest of 3 bits is the leftmost pixel.
+fn glyph(c: char) -> [u8; 5] {
+    match c.to_ascii_uppercase() {
+        '0' => [0b111, 0b101, 0b101, 0b101, 0b111],
+        '1' => [0b010, 0b110, 0b010, 0b010, 0b111],
+        '2' => [0b111, 0b001, 0b111, 0b100, 0b111],
+        '3' => [0b111, 0b001, 0b111, 0b001, 0b111],
+        '4' => [0b101, 0b101, 0b111, 0b001, 0b001],
+        '5' => [0b111, 0b100, 0b111, 0b001, 0b111],
+        '6' => [0b111, 0b100, 0b111, 0b101, 0b111],
+        '7' => [0b111, 0b001, 0b001, 0b001, 0b001],
+        '8' => [0b111, 0b101, 0b111, 0b101, 0b111],
+        '9' => [0b111, 0b101, 0b111, 0b001, 0b111],
+        'A' => [0b010, 0b101, 0b111, 0b101, 0b101],
+        'B' => [0b110, 0b101, 0b110, 0b101, 0b110],
+        'C' => [0b011, 0b100, 0b100, 0b100, 0b011],
+        'D' => [0b110, 0b101, 0b101, 0b101, 0b110],
+        'E' => [0b111, 0b100, 0b110, 0b100, 0b111],
+        'F' => [0b111, 0b100, 0b110, 0b100, 0b100],
+        'G' => [0b011, 0b100, 0b101, 0b101, 0b011],
+        'H' => [0b101, 0b101, 0b111, 0b101, 0b101],
+        'I' => [0b111, 0b010, 0b010, 0b010, 0b111],
+        'J' => [0b001, 0b001, 0b001, 0b101, 0b010],
+        'K' => [0b101, 0b101, 0b110, 0b101, 0b101],
+        'L' => [0b100, 0b100, 0b100, 0b100, 0b111],
+        'M' => [0b101, 0b111, 0b111, 0b101, 0b101],
+        'N' => [0b110, 0b101, 0b101, 0b101, 0b101],
+        'O' => [0b010, 0b101, 0b101, 0b101, 0b010],
+        'P' => [0b110, 0b101, 0b110, 0b100, 0b100],
+        'Q' => [0b010, 0b101, 0b101, 0b110, 0b011],
+        'R' => [0b110, 0b101, 0b110, 0b101, 0b101],
+        'S' => [0b011, 0b100, 0b010, 0b001, 0b110],
+        'T' => [0b111, 0b010, 0b010, 0b010, 0b010],
+        'U' => [0b101, 0b101, 0b101, 0b101, 0b111],
+        'V' => [0b101, 0b101, 0b101, 0b101, 0b010],
+        'W' => [0b101, 0b101, 0b111, 0b111, 0b101],
+        'X' => [0b101, 0b101, 0b010, 0b101, 0b101],
+        'Y' => [0b101, 0b101, 0b010, 0b010, 0b010],
+        'Z' => [0b111, 0b001, 0b010, 0b100, 0b111],
+        ':' => [0b000, 0b010, 0b000, 0b010, 0b000],
+        '.' => [0b000, 0b000, 0b000, 0b000, 0b010],
+        ',' => [0b000, 0b000, 0b000, 0b010, 0b100],
+        '/' => [0b001, 0b001, 0b010, 0b100, 0b100],
+        '-' => [0b000, 0b000, 0b111, 0b000, 0b000],
+        '%' => [0b101, 0b001, 0b010, 0b100, 0b101],
+        _ => [0; 5],
+    }
+}
+
+/// Draws the text lines in the top-left corner of RGB image over a dark background.
+///
+/// Each font pixel takes `scale`x`scale` image pixels, the parts outside the image are clipped.
+pub fn draw_text(data: &mut [u8], dims: (usize, usize), scale: usize, lines: &[String]) {
+    let (cw, ch) = ((GLYPH.0 + 1)*scale, (GLYPH.1 + 1)*scale);
+    let width = lines.iter().map(|l| l.chars().count()).max().unwrap_or(0);
+    if width == 0 {
+        return;
+    }
+    let (bw, bh) = ((width*cw + scale).min(dims.0), (lines.len()*ch + scale).min(dims.1));
+    for y in 0..bh {
+        for b in data[(3*y*dims.0)..(3*(y*dims.0 + bw))].iter_mut() {
+            *b /= 4;
+        }
+    }
+
+    for (j, line) in lines.iter().enumerate() {
+        for (i, c) in line.chars().enumerate() {
+            let rows = glyph(c);
+            for (gy, row) in rows.iter().enumerate() {
+                for gx in 0..GLYPH.0 {
+                    if (row >> (GLYPH.0 - 1 - gx)) & 1 == 0 {
+                        continue;
+                    }
+                    let (x0, y0) = (scale + i*cw + gx*scale, scale + j*ch + gy*scale);
+                    for y in y0..(y0 + scale).min(dims.1) {
+                        for x in x0..(x0 + scale).min(dims.0) {
+                            let k = 3*(y*dims.0 + x);
+                            data[k..(k + 3)].copy_from_slice(&[255; 3]);
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn clip() {
+        let dims = (6, 4);
+        let mut data = vec![200u8; 3*dims.0*dims.1];
+        draw_text(&mut data, dims, 1, &["1 PASS".to_string()]);
+        // Background is darkened, the glyph '1' starts with its middle pixel
+        assert_eq!(data[0], 50);
+        let k = 3*(dims.0 + 2);
+        assert_eq!(data[k], 255);
+    }
+}
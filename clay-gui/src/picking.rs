@@ -0,0 +1,96 @@
+use sdl2::keyboard::{Keycode, Mod};
+use nalgebra::{Vector3, Rotation3};
+use clay_core::{Edit, EditableScene, PickView};
+
+
+/// Keys that edit the selected object, they could not be bound to the camera actions.
+pub const EDIT_KEYS: [Keycode; 12] = [
+    Keycode::J, Keycode::L,
+    Keycode::I, Keycode::K,
+    Keycode::U, Keycode::O,
+    Keycode::Equals, Keycode::Minus,
+    Keycode::R, Keycode::G, Keycode::B,
+    Keycode::Backspace,
+];
+
+/// Object picking and editing state shared between the window and the render function.
+///
+/// The object is picked by the right mouse click. The selected object is moved
+/// with `J`/`L` (left/right), `I`/`K` (up/down) and `U`/`O` (forward/backward) keys,
+/// scaled with `=`/`-` keys and recolored with `R`/`G`/`B` keys (with `Ctrl` to decrease).
+/// `Backspace` drops the selection.
+#[derive(Clone, Debug, Default)]
+pub struct Picking {
+    /// Screen pixel to pick the object at, set by the window on click.
+    pub cursor: Option<(usize, usize)>,
+    /// Index of the selected object.
+    pub selected: Option<usize>,
+    /// Edits of the selected object not yet applied to the scene.
+    pub edits: Vec<Edit>,
+}
+
+impl Picking {
+    /// Picks the object at the cursor and applies the pending edits to the selected object.
+    ///
+    /// Should be called from the render function before the rendering is enqueued.
+    pub fn apply<S: EditableScene, V: PickView>(
+        &mut self,
+        scene: &mut S,
+        view: &V,
+        dims: (usize, usize),
+    ) -> clay_core::Result<()> {
+        if let Some(cursor) = self.cursor.take() {
+            self.selected = scene.pick(&view.pick_ray(cursor, dims));
+        }
+        let edits = self.edits.drain(..).collect::<Vec<_>>();
+        if let Some(index) = self.selected {
+            for edit in edits.iter() {
+                scene.edit(index, edit)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Converts the key into the edit of the selected object.
+    ///
+    /// Movement is made in camera space defined by `map` with the `step` length.
+    pub(crate) fn handle_key(&mut self, key: Keycode, keymod: Mod, map: &Rotation3<f64>, step: f64) -> bool {
+        if self.selected.is_none() {
+            return false;
+        }
+        let sign = if keymod.intersects(Mod::LCTRLMOD | Mod::RCTRLMOD) { -1.0 } else { 1.0 };
+        let shift = |x, y, z| Edit::Translate(step*(map*Vector3::new(x, y, z)));
+        let color = |r, g, b| Edit::Recolor(0.1*sign*Vector3::new(r, g, b));
+        let edit = match key {
+            Keycode::J => shift(-1.0, 0.0, 0.0),
+            Keycode::L => shift(1.0, 0.0, 0.0),
+            Keycode::I => shift(0.0, 1.0, 0.0),
+            Keycode::K => shift(0.0, -1.0, 0.0),
+            Keycode::U => shift(0.0, 0.0, -1.0),
+            Keycode::O => shift(0.0, 0.0, 1.0),
+            Keycode::Equals => Edit::Scale(1.1),
+            Keycode::Minus => Edit::Scale(1.0/1.1),
+            Keycode::R => color(1.0, 0.0, 0.0),
+            Keycode::G => color(0.0, 1.0, 0.0),
+            Keycode::B => color(0.0, 0.0, 1.0),
+            Keycode::Backspace => {
+                self.selected = None;
+                return true;
+            },
+            _ => return false,
+        };
+        self.edits.push(edit);
+        true
+    }
+}
+
+/// Tints the pixels of RGB image which object index is `selected`.
+pub fn highlight(data: &mut [u8], object_ids: &[f32], selected: usize) {
+    for (pixel, id) in data.chunks_mut(3).zip(object_ids.iter()) {
+        if *id as i64 == selected as i64 {
+            pixel[0] = pixel[0]/2 + 128;
+            pixel[1] = pixel[1]/2 + 64;
+            pixel[2] /= 2;
+        }
+    }
+}
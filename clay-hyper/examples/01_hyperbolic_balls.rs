@@ -0,0 +1,87 @@
+use std::{
+    env,
+    f64::consts::PI,
+};
+use ocl::{Platform, Device};
+use nalgebra::{Vector3, Rotation3};
+use clay_core::{
+    Context,
+    shape::*, material::*, object::Covered,
+    shape_select,
+};
+use clay::{
+    material::*, DefaultWorker,
+    background::{GradientBackground as GradBg},
+};
+use clay_hyper::{
+    map::Moebius, shape::*,
+    scene::HyperScene, view::HyperView,
+};
+use clay_gui::{Window};
+
+shape_select!(MyShape {
+    Ball(TB=ShapeMapper<HyperBall, Moebius>),
+    Plane(TP=ShapeMapper<HyperPlane, Moebius>),
+    Horosphere(TH=ShapeMapper<Horosphere, Moebius>),
+});
+type MyObject = Covered<MyShape, Colored<Diffuse>>;
+type MyScene = HyperScene<MyObject, GradBg>;
+type MyView = HyperView;
+
+
+fn main() {
+    // Parse args to select OpenCL platform
+    let args = env::args().collect::<Vec<_>>();
+    let platform = if args.len() > 1 {
+        let platform_list = Platform::list();
+        let index = args[1].parse::<usize>().unwrap();
+        assert!(platform_list.len() > index);
+        platform_list[index]
+    } else {
+        Platform::default()
+    };
+    let device = Device::first(platform).unwrap();
+
+    let context = Context::new(platform, device).unwrap();
+    let mut worker = DefaultWorker::<MyScene, MyView>::builder().unwrap()
+    .build(&context).unwrap();
+
+    let diffuse = |r, g, b| Diffuse {}.color_with(Vector3::new(r, g, b));
+    let mut scene = HyperScene::builder(GradBg::new(
+        Vector3::new(0.8, 0.8, 0.8), Vector3::new(0.2, 0.2, 0.2),
+    ));
+    // Balls of the same size in a ring look smaller as they get farther
+    for i in 0..8 {
+        let angle = 2.0*PI*(i as f64)/8.0;
+        let dir = Vector3::new(angle.cos(), angle.sin(), 0.0);
+        for k in 1..4 {
+            scene.add(
+                MyShape::from(HyperBall::new(0.4).map(Moebius::translation(1.5*(k as f64)*dir)))
+                .cover(diffuse(0.9, 0.3 + 0.2*(k as f64), 0.3))
+            );
+        }
+    }
+    // Floor plane at the distance of one unit below the origin
+    scene.add(
+        MyShape::from(HyperPlane::new().map(Moebius::translation(-Vector3::z())))
+        .cover(diffuse(0.5, 0.5, 0.9))
+    );
+    // Horosphere centered at the ideal point in the `y` direction
+    scene.add(
+        MyShape::from(Horosphere::new().map(Moebius::new(
+            *Rotation3::from_axis_angle(&Vector3::x_axis(), -0.5*PI).matrix(),
+            clay_hyper::math::exp(&(4.0*Vector3::y())),
+        )))
+        .cover(diffuse(0.3, 0.9, 0.3))
+    );
+    let scene = scene.build(&context).unwrap();
+
+    let mut window = Window::new((1000, 800)).unwrap();
+
+    window.start(&context, |screen, pos, map, settings| {
+        worker.set_denoise(settings.denoise.clone());
+        // Euclidean camera motion is mapped to the hyperbolic space from the origin
+        let view = HyperView::from_tangent(pos, *map.matrix());
+        worker.enqueue(screen, &scene, &view)
+    }).unwrap();
+}
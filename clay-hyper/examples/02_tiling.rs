@@ -0,0 +1,73 @@
+use std::env;
+use ocl::{Platform, Device};
+use nalgebra::Vector3;
+use clay_core::{
+    Context,
+    shape::*, material::*, object::Covered,
+    shape_select,
+};
+use clay::{
+    material::*, DefaultWorker,
+    background::{GradientBackground as GradBg},
+};
+use clay_hyper::{
+    map::Moebius, shape::*,
+    scene::TiledScene, view::HyperView,
+    tiling::RegularTiling,
+};
+use clay_gui::{Window};
+
+shape_select!(MyShape {
+    Ball(TB=ShapeMapper<HyperBall, Moebius>),
+    Plane(TP=HyperPlane),
+});
+type MyObject = Covered<MyShape, Colored<Diffuse>>;
+type MyScene = TiledScene<MyObject, GradBg>;
+type MyView = HyperView;
+
+
+fn main() {
+    // Parse args to select OpenCL platform
+    let args = env::args().collect::<Vec<_>>();
+    let platform = if args.len() > 1 {
+        let platform_list = Platform::list();
+        let index = args[1].parse::<usize>().unwrap();
+        assert!(platform_list.len() > index);
+        platform_list[index]
+    } else {
+        Platform::default()
+    };
+    let device = Device::first(platform).unwrap();
+
+    let context = Context::new(platform, device).unwrap();
+    let mut worker = DefaultWorker::<MyScene, MyView>::builder().unwrap()
+    .build(&context).unwrap();
+
+    // Heptagons meeting by three at each vertex
+    let tiling = RegularTiling::new(7, 3);
+    let diffuse = |r, g, b| Diffuse {}.color_with(Vector3::new(r, g, b));
+    let mut scene = TiledScene::builder(tiling.walls(), GradBg::new(
+        Vector3::new(0.8, 0.8, 0.8), Vector3::new(0.2, 0.2, 0.2),
+    ));
+    // The floor is orthogonal to the walls, so its copies form the whole plane
+    scene.add(MyShape::from(HyperPlane::new()).cover(diffuse(0.5, 0.5, 0.9)));
+    scene.add(
+        MyShape::from(HyperBall::new(0.25).map(Moebius::translation(0.4*Vector3::z())))
+        .cover(diffuse(0.9, 0.5, 0.3))
+    );
+    // Balls at the corners of the domain are cut by the walls, their parts are joined by reflections
+    scene.add(
+        MyShape::from(HyperBall::at(0.15, tiling.vertices()[0]))
+        .cover(diffuse(0.3, 0.9, 0.3))
+    );
+    let scene = scene.build(&context).unwrap();
+
+    let mut window = Window::new((1000, 800)).unwrap();
+
+    window.start(&context, |screen, pos, map, settings| {
+        worker.set_denoise(settings.denoise.clone());
+        // The camera is folded into the central tile while rendering
+        let view = HyperView::from_tangent(pos + Vector3::new(0.0, 0.0, 0.3), *map.matrix());
+        worker.enqueue(screen, &scene, &view)
+    }).unwrap();
+}
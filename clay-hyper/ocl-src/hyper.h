@@ -0,0 +1,112 @@
+#pragma once
+
+// Poincare ball model of hyperbolic space.
+//
+// Points lie inside the unit ball. The model is conformal, so the direction
+// of a ray at its start point is an ordinary unit vector and angles are Euclidean.
+// Distances along geodesics are hyperbolic.
+
+
+// Mobius addition `a (+) x` - the isometry that moves the origin to `a`.
+// Also valid for `x` on the boundary (ideal points).
+float3 hyper_add(float3 a, float3 x) {
+    float ax = dot(a, x), aa = dot(a, a), xx = dot(x, x);
+    return ((1.0f + 2.0f*ax + xx)*a + (1.0f - aa)*x)/(1.0f + 2.0f*ax + aa*xx);
+}
+
+// Point at the distance `t` from `x` along the geodesic in direction `d`.
+float3 hyper_advance(float3 x, float3 d, float t) {
+    return hyper_add(x, tanh(0.5f*t)*d);
+}
+
+// Ideal point where the geodesic from `x` in direction `d` ends.
+float3 hyper_ideal(float3 x, float3 d) {
+    return hyper_add(x, d);
+}
+
+// Direction at `x` of the geodesic ending at the ideal point `e`.
+float3 hyper_tangent(float3 x, float3 e) {
+    return normalize(hyper_add(-x, e));
+}
+
+float hyper_distance(float3 x, float3 y) {
+    float3 d = x - y;
+    return acosh(1.0f + 2.0f*dot(d, d)/((1.0f - dot(x, x))*(1.0f - dot(y, y))));
+}
+
+// Parallel transport of the direction `v` at `x` along the geodesic to `y`.
+//
+// It is the gyration `gyr[y, -x]`, the rotation left after moving `x` to `y` by Mobius additions.
+float3 hyper_transport(float3 x, float3 v, float3 y) {
+    return normalize(hyper_add(-hyper_add(y, -x), hyper_add(y, hyper_add(-x, v))));
+}
+
+
+// Hyperboloid model is used to intersect geodesics with shapes.
+// Time coordinate is stored in `x` component and the spatial ones in `yzw`.
+
+float hyper_mdot(float4 a, float4 b) {
+    return -a.x*b.x + dot(a.yzw, b.yzw);
+}
+
+float4 hyper_lift(float3 x) {
+    float xx = dot(x, x);
+    return (float4)(1.0f + xx, 2.0f*x)/(1.0f - xx);
+}
+
+float4 hyper_lift_dir(float3 x, float3 d) {
+    float k = 2.0f*dot(x, d)/(1.0f - dot(x, x));
+    return (float4)(k, d + k*x);
+}
+
+float3 hyper_drop(float4 p) {
+    return p.yzw/(1.0f + p.x);
+}
+
+float3 hyper_drop_dir(float3 x, float4 v) {
+    return v.yzw - x*v.x;
+}
+
+// Intersection of the geodesic `p*cosh(t) + v*sinh(t)` with the region `<x, q> >= c`.
+//
+// The ray must start outside of the region. Balls, horoballs and half-spaces
+// are described this way. The returned normal is pointing outward.
+bool hyper_region_hit(
+    float4 p, float4 v, float4 q, float c,
+    float *enter, float *exit, float4 *norm
+) {
+    float pq = hyper_mdot(p, q), vq = hyper_mdot(v, q);
+    if (pq >= c) {
+        return false;
+    }
+    // With `u = exp(t)` the boundary is at the roots of `a*u^2 - 2*c*u + b`,
+    // and the ray starts at `u = 1` where the polynomial is negative
+    float a = pq + vq, b = pq - vq;
+    float d = c*c - a*b;
+    if (d < 0.0f) {
+        return false;
+    }
+    float s = c + copysign(sqrt(d), c);
+    if (s == 0.0f) {
+        return false;
+    }
+    float r0 = (a != 0.0f) ? s/a : INFINITY;
+    float r1 = b/s;
+    float u0 = min(r0, r1), u1 = max(r0, r1);
+    if (!(u1 > 1.0f)) {
+        return false;
+    }
+    // The first root after the start enters the region and the next one exits it
+    float ue = u1, ux = INFINITY;
+    if (u0 > 1.0f) {
+        ue = u0;
+        ux = u1;
+    }
+    *enter = log(ue);
+    *exit = log(ux);
+
+    float4 x = p*cosh(*enter) + v*sinh(*enter);
+    float4 n = -(q + hyper_mdot(q, x)*x);
+    *norm = n/sqrt(hyper_mdot(n, n));
+    return true;
+}
@@ -0,0 +1,35 @@
+#pragma once
+
+#include <clay_core/matrix.h>
+#include <clay_core/map/map.h>
+#include <clay_hyper/hyper.h>
+
+
+// Isometry `x -> shift (+) rotation*x`.
+//
+// Points and ideal points are mapped by `abs` and `abs_inv`.
+// The `rel` and `norm` methods map vectors at the origin,
+// for vectors at other points use ideal points (see `clay_hyper/space.h`).
+//
+// The rotation matrix is packed by columns, so it is loaded transposed.
+
+MAP_RET moebius_rel(MAP_ARGS_DEF) {
+    return matrix3_dot(matrix3_transpose(moebius_load(ibuf, fbuf).rotation), v);
+}
+
+MAP_RET moebius_abs(MAP_ARGS_DEF) {
+    return hyper_add(moebius_load(ibuf, fbuf).shift, moebius_rel(MAP_ARGS));
+}
+
+MAP_RET moebius_rel_inv(MAP_ARGS_DEF) {
+    return matrix3_dot(moebius_load(ibuf, fbuf).rotation, v);
+}
+
+MAP_RET moebius_abs_inv(MAP_ARGS_DEF) {
+    float3 shift = moebius_load(ibuf, fbuf).shift;
+    return moebius_rel_inv(MAP_ARGS_V(hyper_add(-shift, v)));
+}
+
+MAP_RET moebius_norm(MAP_ARGS_DEF) {
+    return moebius_rel(MAP_ARGS);
+}
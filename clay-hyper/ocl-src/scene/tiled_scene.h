@@ -0,0 +1,202 @@
+#pragma once
+
+#include <clay_core/random.h>
+#include <clay_core/trace.h>
+#include <clay_hyper/hyper.h>
+
+
+#define SCENE_ARGS_DEF \
+    __global const int *object_buffer_int, \
+    __global const float *object_buffer_float, \
+    int object_size_int, \
+    int object_size_float, \
+    int objects_count, \
+    \
+    __global const int *wall_buffer_int, \
+    __global const float *wall_buffer_float, \
+    int wall_size_int, \
+    int wall_size_float, \
+    int walls_count, \
+    \
+    BACKGROUND_ARGS_DEF
+
+#define SCENE_ARGS \
+    object_buffer_int, \
+    object_buffer_float, \
+    object_size_int, \
+    object_size_float, \
+    objects_count, \
+    \
+    wall_buffer_int, \
+    wall_buffer_float, \
+    wall_size_int, \
+    wall_size_float, \
+    walls_count, \
+    \
+    BACKGROUND_ARGS
+
+#define MAX_DEPTH 4
+
+// Rays folded more times than this are considered lost
+#define MAX_FOLDS 64
+
+
+// Wall plane in hyperboloid model, the origin is on its negative side.
+float4 wall_vector(__global const int *ibuf, __global const float *fbuf) {
+    Wall wall = wall_load(ibuf, fbuf);
+    return (float4)(sinh(wall.distance), cosh(wall.distance)*wall.normal);
+}
+
+// Moves the ray by the distance `t` to the wall and reflects it in the wall.
+Ray wall_fold(Ray ray, float t, float4 q) {
+    float4 p = hyper_lift(ray.start), v = hyper_lift_dir(ray.start, ray.dir);
+    float4 x = p*cosh(t) + v*sinh(t);
+    float4 w = p*sinh(t) + v*cosh(t);
+    x -= 2.0f*hyper_mdot(x, q)*q;
+    w -= 2.0f*hyper_mdot(w, q)*q;
+    ray.start = hyper_drop(x);
+    ray.dir = normalize(hyper_drop_dir(ray.start, w));
+    return ray;
+}
+
+// Moves the ray starting outside of the domain into it by reflections in the walls.
+Ray domain_enter(Ray ray, SCENE_ARGS_DEF) {
+    int fold = 0;
+    for (fold = 0; fold < MAX_FOLDS; ++fold) {
+        float4 p = hyper_lift(ray.start);
+        int i = 0;
+        for (i = 0; i < walls_count; ++i) {
+            float4 q = wall_vector(wall_buffer_int + wall_size_int*i, wall_buffer_float + wall_size_float*i);
+            if (hyper_mdot(p, q) > 0.0f) {
+                ray = wall_fold(ray, 0.0f, q);
+                break;
+            }
+        }
+        if (i == walls_count) {
+            break;
+        }
+    }
+    return ray;
+}
+
+bool scene_trace(
+    uint *seed,
+    Ray ray,
+    Ray *new_ray,
+    float3 *color,
+    TraceInfo *info,
+    SCENE_ARGS_DEF
+) {
+    // Distance traveled in the previous tiles
+    float traveled = 0.0f;
+    // Wall the ray has been folded through
+    int last_wall = -1;
+
+    int fold = 0;
+    for (fold = 0; fold < MAX_FOLDS; ++fold) {
+        int hit_idx = -1;
+        float hit_enter = INFINITY;
+        float3 hit_norm;
+
+        int i = 0;
+        for (i = 0; i < objects_count; ++i) {
+            float enter, exit;
+            float3 norm;
+
+            if (ray.origin == i) {
+                continue;
+            }
+
+            __global const int *ibuf = object_buffer_int + object_size_int*i;
+            __global const float *fbuf = object_buffer_float + object_size_float*i;
+            if (__object_hit(seed, ray, ibuf, fbuf, &enter, &exit, &norm)) {
+                if (enter < hit_enter) {
+                    hit_enter = enter;
+                    hit_norm = norm;
+                    hit_idx = i;
+                }
+            }
+        }
+
+        // The ray leaves the domain before the hit
+        int wall_idx = -1;
+        float wall_enter = hit_enter;
+        float4 wall_q;
+        float4 p = hyper_lift(ray.start), v = hyper_lift_dir(ray.start, ray.dir);
+        for (i = 0; i < walls_count; ++i) {
+            float enter, exit;
+            float4 norm;
+
+            if (last_wall == i) {
+                continue;
+            }
+
+            float4 q = wall_vector(wall_buffer_int + wall_size_int*i, wall_buffer_float + wall_size_float*i);
+            if (hyper_region_hit(p, v, q, 0.0f, &enter, &exit, &norm)) {
+                if (enter < wall_enter) {
+                    wall_enter = enter;
+                    wall_q = q;
+                    wall_idx = i;
+                }
+            }
+        }
+        if (wall_idx >= 0) {
+            ray = wall_fold(ray, wall_enter, wall_q);
+            // The copy of the object the ray came from could be hit in the next tile
+            ray.origin = -1;
+            last_wall = wall_idx;
+            traveled += wall_enter;
+            continue;
+        }
+
+        if (hit_idx >= 0) {
+            float3 hit_pos = hyper_advance(ray.start, ray.dir, hit_enter);
+            trace_info_hit(info, traveled + hit_enter, hit_norm, hit_idx);
+
+            __global const int *ibuf = object_buffer_int + object_size_int*hit_idx;
+            __global const float *fbuf = object_buffer_float + object_size_float*hit_idx;
+            float3 prev_color = *color;
+            bool bounce = __object_bounce(
+                seed, ray, hit_pos, normalize(hit_norm),
+                false, (float3)(0.0f), 0.0f,
+                ibuf, fbuf, new_ray, color
+            );
+            trace_info_albedo(info, bounce ? new_ray->color : *color - prev_color);
+            if (bounce) {
+                new_ray->origin = hit_idx;
+                new_ray->time = ray.time;
+                return true;
+            }
+            return false;
+        }
+
+        // Background is looked up by the ideal point the ray goes to
+        Ray ideal_ray = ray;
+        ideal_ray.dir = hyper_ideal(ray.start, ray.dir);
+        *color += __background(ideal_ray, BACKGROUND_ARGS);
+        return false;
+    }
+    return false;
+}
+
+float3 __scene_trace(
+    uint *seed,
+    Ray ray,
+    TraceInfo *info,
+    SCENE_ARGS_DEF
+) {
+    float3 color = (float3)(0.0f);
+    int i = 0;
+    // The camera could be in any tile
+    Ray current_ray = domain_enter(ray, SCENE_ARGS);
+    for (i = 0; i < MAX_DEPTH; ++i) {
+        Ray next_ray = ray_new();
+        info->rays += 1;
+        bool bounce = scene_trace(seed, current_ray, &next_ray, &color, info, SCENE_ARGS);
+        if (!bounce) {
+            break;
+        }
+        current_ray = next_ray;
+    }
+    return color;
+}
@@ -0,0 +1,17 @@
+#pragma once
+
+#include <clay_core/ray.h>
+#include <clay_core/shape/shape.h>
+#include <clay_hyper/shape/region.h>
+
+
+// Ball of the hyperbolic radius centered at the origin.
+SHAPE_HIT_RET hyper_ball_hit(
+    SHAPE_HIT_ARGS_DEF
+) {
+    float radius = hyper_ball_load(ibuf, fbuf).radius;
+    return hyper_shape_hit(
+        ray, (float4)(1.0f, 0.0f, 0.0f, 0.0f), -cosh(radius),
+        enter, exit, norm
+    );
+}
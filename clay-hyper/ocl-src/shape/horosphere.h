@@ -0,0 +1,16 @@
+#pragma once
+
+#include <clay_core/ray.h>
+#include <clay_core/shape/shape.h>
+#include <clay_hyper/shape/region.h>
+
+
+// Horosphere passing through the origin and centered at the ideal point `(0, 0, 1)`.
+SHAPE_HIT_RET horosphere_hit(
+    SHAPE_HIT_ARGS_DEF
+) {
+    return hyper_shape_hit(
+        ray, (float4)(1.0f, 0.0f, 0.0f, 1.0f), -1.0f,
+        enter, exit, norm
+    );
+}
@@ -0,0 +1,22 @@
+#pragma once
+
+#include <clay_core/ray.h>
+#include <clay_core/shape/shape.h>
+#include <clay_hyper/shape/region.h>
+
+
+// Two-sided plane `z = 0` passing through the origin.
+SHAPE_HIT_RET hyper_plane_hit(
+    SHAPE_HIT_ARGS_DEF
+) {
+    float4 q = (float4)(0.0f, 0.0f, 0.0f, 1.0f);
+    // The ray hits the half-space it doesn't start in
+    if (ray.start.z > 0.0f) {
+        q = -q;
+    }
+    if (!hyper_shape_hit(ray, q, 0.0f, enter, exit, norm)) {
+        return false;
+    }
+    *exit = *enter;
+    return true;
+}
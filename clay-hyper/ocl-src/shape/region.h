@@ -0,0 +1,20 @@
+#pragma once
+
+#include <clay_core/ray.h>
+#include <clay_hyper/hyper.h>
+
+
+// Hits the region with the ray in Poincare ball and returns the normal in it.
+bool hyper_shape_hit(
+    Ray ray, float4 q, float c,
+    float *enter, float *exit, float3 *norm
+) {
+    float4 p = hyper_lift(ray.start), v = hyper_lift_dir(ray.start, ray.dir);
+    float4 n;
+    if (!hyper_region_hit(p, v, q, c, enter, exit, &n)) {
+        return false;
+    }
+    float3 x = hyper_advance(ray.start, ray.dir, *enter);
+    *norm = hyper_drop_dir(x, n);
+    return true;
+}
@@ -0,0 +1,33 @@
+#pragma once
+
+#include <clay_hyper/hyper.h>
+
+// Hyperbolic space in Poincare ball model, implements `clay_core/space/space.h`.
+
+typedef float3 space_point;
+typedef float3 space_vector;
+
+space_point space_advance(space_point x, space_vector d, float t) {
+    return hyper_advance(x, d, t);
+}
+
+float space_distance(space_point x, space_point y) {
+    return hyper_distance(x, y);
+}
+
+space_vector space_transport(space_point x, space_vector v, space_point y) {
+    return hyper_transport(x, v, y);
+}
+
+// Background is looked up by the ideal point the ray goes to
+space_vector space_background_dir(space_point x, space_vector d) {
+    return hyper_ideal(x, d);
+}
+
+// Maps must be isometries. Directions are carried as ideal points,
+// so only `abs` and `abs_inv` of the map are used and distances need no rescaling.
+#define SPACE_MAP_DIR_INV(map, x, d, new_x, di, df) \
+    hyper_tangent((new_x), map##_abs_inv(MAP_ARGS_VB(hyper_ideal((x), (d)), di, df)))
+
+#define SPACE_MAP_NORM(map, x, n, new_x, di, df) \
+    hyper_tangent((new_x), map##_abs(MAP_ARGS_VB(hyper_ideal((x), normalize(n)), di, df)))
@@ -1,7 +1,19 @@
+pub use clay_core as core;
+
+pub use clay_core::Error;
+pub use clay_core::Result;
+
+pub mod math;
+pub mod space;
+pub mod map;
+pub mod shape;
+pub mod tiling;
+
+pub mod scene;
+pub mod view;
+
+pub mod source;
+pub use source::*;
+
 #[cfg(test)]
-mod tests {
-    #[test]
-    fn it_works() {
-        assert_eq!(2 + 2, 4);
-    }
-}
+mod test;
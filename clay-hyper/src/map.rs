@@ -0,0 +1,77 @@
+use nalgebra::{Vector3, Matrix3};
+use clay_core::{pack::*, class::*, map::*};
+use crate::math;
+
+
+/// Isometry of the hyperbolic space in Poincare ball model.
+///
+/// It rotates the space around the origin and then moves the origin to `shift`
+/// which must be inside the unit ball.
+#[derive(Clone, Debug, Pack, Unpack, Instance)]
+#[instance(class = "MapClass", source = "clay_hyper/map/moebius.h", name = "moebius")]
+pub struct Moebius {
+    pub rotation: Matrix3<f64>,
+    pub shift: Vector3<f64>,
+}
+
+impl Moebius {
+    pub fn new(rotation: Matrix3<f64>, shift: Vector3<f64>) -> Self {
+        assert!(shift.norm() < 1.0, "shift must be inside the unit ball");
+        Self { rotation, shift }
+    }
+    pub fn identity() -> Self {
+        Self::new(Matrix3::identity(), Vector3::zeros())
+    }
+    pub fn rotation(rotation: Matrix3<f64>) -> Self {
+        Self::new(rotation, Vector3::zeros())
+    }
+    /// Moves the origin by the hyperbolic distance of `dir` length in its direction.
+    pub fn translation(dir: Vector3<f64>) -> Self {
+        Self::new(Matrix3::identity(), math::exp(&dir))
+    }
+
+    pub fn apply(&self, x: &Vector3<f64>) -> Vector3<f64> {
+        math::add(&self.shift, &(self.rotation*x))
+    }
+    pub fn apply_inv(&self, x: &Vector3<f64>) -> Vector3<f64> {
+        self.rotation.transpose()*math::add(&-self.shift, x)
+    }
+
+    pub fn inverse(&self) -> Self {
+        let rotation = self.rotation.transpose();
+        Self { shift: -(rotation*self.shift), rotation }
+    }
+
+    /// Isometry applying this one and then `other`.
+    pub fn then(&self, other: &Moebius) -> Self {
+        let shift = other.apply(&self.apply(&Vector3::zeros()));
+        // Moving the result back to the origin leaves a rotation,
+        // its columns are the images of ideal points on the axes
+        let rotation = Matrix3::from_fn(|i, j| {
+            let e = Vector3::from_fn(|k, _| if k == j { 1.0 } else { 0.0 });
+            math::add(&-shift, &other.apply(&self.apply(&e)))[i]
+        });
+        Self { rotation, shift }
+    }
+}
+
+impl Map for Moebius {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use nalgebra::Rotation3;
+
+    #[test]
+    fn compose() {
+        let a = Moebius::new(
+            *Rotation3::from_euler_angles(0.1, 0.2, 0.3).matrix(),
+            Vector3::new(0.2, -0.3, 0.1),
+        );
+        let b = Moebius::translation(Vector3::new(0.0, 1.0, 2.0));
+        let x = Vector3::new(-0.4, 0.3, 0.2);
+        assert!((a.then(&b).apply(&x) - b.apply(&a.apply(&x))).norm() < 1e-9);
+        assert!((a.inverse().apply(&a.apply(&x)) - x).norm() < 1e-9);
+        assert!((a.apply_inv(&a.apply(&x)) - x).norm() < 1e-9);
+    }
+}
@@ -0,0 +1,76 @@
+//! Host-side operations in the Poincare ball model, same as in `clay_hyper/hyper.h`.
+
+use nalgebra::Vector3;
+
+
+/// Mobius addition `a (+) x` - the isometry that moves the origin to `a`.
+///
+/// It is also valid for `x` on the boundary of the ball.
+pub fn add(a: &Vector3<f64>, x: &Vector3<f64>) -> Vector3<f64> {
+    let (ax, aa, xx) = (a.dot(x), a.dot(a), x.dot(x));
+    ((1.0 + 2.0*ax + xx)*a + (1.0 - aa)*x)/(1.0 + 2.0*ax + aa*xx)
+}
+
+/// Point at the distance `t` from `x` along the geodesic in the direction `dir`.
+pub fn advance(x: &Vector3<f64>, dir: &Vector3<f64>, t: f64) -> Vector3<f64> {
+    add(x, &((0.5*t).tanh()*dir.normalize()))
+}
+
+/// Hyperbolic distance between two points.
+pub fn distance(x: &Vector3<f64>, y: &Vector3<f64>) -> f64 {
+    let d = 2.0*(x - y).norm_squared()/((1.0 - x.norm_squared())*(1.0 - y.norm_squared()));
+    (1.0 + d).acosh()
+}
+
+/// Parallel transport of the vector `v` at `x` along the geodesic to `y`.
+///
+/// It is the gyration `gyr[y, -x]` that preserves Euclidean length,
+/// so it is valid for the directions of the conformal model.
+pub fn transport(x: &Vector3<f64>, v: &Vector3<f64>, y: &Vector3<f64>) -> Vector3<f64> {
+    add(&-add(y, &-x), &add(y, &add(&-x, v)))
+}
+
+/// Maps the vector of the tangent space at the origin to the point
+/// at the distance of the vector length in its direction.
+pub fn exp(v: &Vector3<f64>) -> Vector3<f64> {
+    let len = v.norm();
+    if len > 0.0 {
+        (0.5*len).tanh()*v/len
+    } else {
+        Vector3::zeros()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn isometry() {
+        let (a, x, y) = (
+            Vector3::new(0.3, -0.2, 0.5),
+            Vector3::new(-0.6, 0.1, 0.2),
+            Vector3::new(0.1, 0.7, -0.4),
+        );
+        assert!((distance(&add(&a, &x), &add(&a, &y)) - distance(&x, &y)).abs() < 1e-9);
+        assert!((add(&-a, &add(&a, &x)) - x).norm() < 1e-9);
+        assert!((distance(&Vector3::zeros(), &exp(&(2.0*Vector3::x()))) - 2.0).abs() < 1e-9);
+
+        let e = add(&a, &Vector3::y());
+        assert!((e.norm() - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn transport_along_geodesic() {
+        let (x, y) = (Vector3::new(0.2, -0.3, 0.1), Vector3::new(-0.4, 0.2, 0.3));
+        // Tangent of the geodesic is transported to the tangent
+        let e = add(&x, &add(&-x, &y).normalize());
+        let (tx, ty) = (add(&-x, &e).normalize(), add(&-y, &e).normalize());
+        assert!((transport(&x, &tx, &y).normalize() - ty).norm() < 1e-9);
+        // Angles are preserved
+        let v = Vector3::new(0.3, 0.5, -0.2).normalize();
+        let w = transport(&x, &v, &y);
+        assert!((w.norm() - 1.0).abs() < 1e-9);
+        assert!((w.dot(&ty) - v.dot(&tx)).abs() < 1e-9);
+    }
+}
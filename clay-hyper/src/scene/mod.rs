@@ -0,0 +1,11 @@
+mod tiled_scene;
+pub use tiled_scene::*;
+
+use clay::scene::ListScene;
+use crate::space::Hyperbolic;
+
+/// List of objects in hyperbolic space.
+///
+/// Rays travel along geodesics of Poincare ball model,
+/// the background is looked up by the ideal point of the ray.
+pub type HyperScene<O, B> = ListScene<O, B, Hyperbolic>;
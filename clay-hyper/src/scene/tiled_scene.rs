@@ -0,0 +1,108 @@
+use std::collections::HashSet;
+use ocl::{self, builders::KernelBuilder};
+use clay_core::{
+    Context,
+    InstanceBuffer,
+    class::*,
+    object::*,
+    layout::*,
+    Background,
+    Push, Scene,
+};
+use crate::{space::Hyperbolic, tiling::Wall};
+
+
+pub struct TiledSceneBuilder<O: Object, B: Background> {
+    objects: Vec<O>,
+    walls: Vec<Wall>,
+    background: B,
+}
+
+impl<O: Object, B: Background> TiledSceneBuilder<O, B> {
+    pub fn add(&mut self, object: O) -> &mut Self {
+        self.objects.push(object);
+        self
+    }
+    pub fn build(self, context: &Context) -> crate::Result<TiledScene<O, B>> {
+        TiledScene::new(context, self.objects, self.walls, self.background)
+    }
+}
+
+/// Hyperbolic scene repeated by reflections in the walls of the fundamental domain.
+///
+/// The objects should be placed inside the domain bounded by the walls.
+/// When a ray leaves the domain it is reflected back through the wall,
+/// so the domain is seen from the inside of every tile.
+/// The tiling is correct only when the walls meet at angles `pi/k`, e.g. for `RegularTiling` walls.
+pub struct TiledScene<O: Object, B: Background> {
+    objects: Vec<O>,
+    walls: Vec<Wall>,
+    object_buffer: InstanceBuffer<O>,
+    wall_buffer: InstanceBuffer<Wall>,
+    background: B,
+}
+
+impl<O: Object, B: Background> TiledScene<O, B> {
+    pub fn new(
+        context: &Context,
+        objects: Vec<O>,
+        walls: Vec<Wall>,
+        background: B,
+    ) -> crate::Result<Self> {
+        let object_buffer = InstanceBuffer::new(context, &objects)?;
+        let wall_buffer = InstanceBuffer::new(context, &walls)?;
+        Ok(Self { objects, walls, object_buffer, wall_buffer, background })
+    }
+
+    pub fn objects(&self) -> &[O] {
+        &self.objects
+    }
+    pub fn walls(&self) -> &[Wall] {
+        &self.walls
+    }
+
+    pub fn builder(walls: Vec<Wall>, background: B) -> TiledSceneBuilder<O, B> {
+        TiledSceneBuilder { objects: Vec::new(), walls, background }
+    }
+}
+
+impl<O: Object, B: Background> Scene for TiledScene<O, B> {
+    type Space = Hyperbolic;
+
+    fn source(cache: &mut HashSet<u64>) -> String {
+        [
+            O::source(cache),
+            B::source(cache),
+            layout_source::<Wall>(),
+            ObjectClass::methods().into_iter().map(|method| {
+                format!(
+                    "#define __object_{} {}_{}",
+                    method, O::inst_name(), method,
+                )
+            }).collect::<Vec<_>>().join("\n"),
+            "#include <clay_hyper/scene/tiled_scene.h>".to_string(),
+        ]
+        .join("\n")
+    }
+}
+
+impl<O: Object, B: Background> Push for TiledScene<O, B> {
+    fn args_def(kb: &mut KernelBuilder) {
+        InstanceBuffer::<O>::args_def(kb);
+        InstanceBuffer::<Wall>::args_def(kb);
+        B::args_def(kb);
+    }
+    fn args_set(&self, i: usize, k: &mut ocl::Kernel) -> crate::Result<()> {
+        let mut j = i;
+        self.object_buffer.args_set(j, k)?;
+        j += InstanceBuffer::<O>::args_count();
+        self.wall_buffer.args_set(j, k)?;
+        j += InstanceBuffer::<Wall>::args_count();
+        self.background.args_set(j, k)
+    }
+    fn args_count() -> usize {
+        InstanceBuffer::<O>::args_count() +
+        InstanceBuffer::<Wall>::args_count() +
+        B::args_count()
+    }
+}
@@ -0,0 +1,23 @@
+use nalgebra::{Vector3, Matrix3};
+use clay_core::{pack::*, class::*, shape::*};
+use crate::map::Moebius;
+
+
+/// Ball of the hyperbolic `radius` centered at the origin.
+#[derive(Clone, Debug, Pack, Unpack, Instance)]
+#[instance(class = "ShapeClass", source = "clay_hyper/shape/ball.h", name = "hyper_ball")]
+pub struct HyperBall {
+    pub radius: f64,
+}
+
+impl HyperBall {
+    pub fn new(radius: f64) -> Self {
+        Self { radius }
+    }
+    /// Ball centered at the point of Poincare ball model.
+    pub fn at(radius: f64, pos: Vector3<f64>) -> ShapeMapper<Self, Moebius> {
+        Self::new(radius).map(Moebius::new(Matrix3::identity(), pos))
+    }
+}
+
+impl Shape for HyperBall {}
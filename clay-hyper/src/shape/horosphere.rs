@@ -0,0 +1,17 @@
+use clay_core::{pack::*, class::*, shape::*};
+
+
+/// Horosphere passing through the origin and centered at the ideal point `(0, 0, 1)`.
+///
+/// It is a limit of spheres with the growing radius, its inner geometry is Euclidean.
+#[derive(Clone, Debug, Default, Pack, Unpack, Instance)]
+#[instance(class = "ShapeClass", source = "clay_hyper/shape/horosphere.h", name = "horosphere")]
+pub struct Horosphere {}
+
+impl Horosphere {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl Shape for Horosphere {}
@@ -0,0 +1,6 @@
+mod ball;
+pub use ball::HyperBall;
+mod plane;
+pub use plane::HyperPlane;
+mod horosphere;
+pub use horosphere::Horosphere;
@@ -0,0 +1,17 @@
+use clay_core::{pack::*, class::*, shape::*};
+
+
+/// Two-sided totally geodesic plane `z = 0`.
+///
+/// Other planes are obtained by moving it with isometries.
+#[derive(Clone, Debug, Default, Pack, Unpack, Instance)]
+#[instance(class = "ShapeClass", source = "clay_hyper/shape/plane.h", name = "hyper_plane")]
+pub struct HyperPlane {}
+
+impl HyperPlane {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl Shape for HyperPlane {}
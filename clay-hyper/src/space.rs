@@ -0,0 +1,23 @@
+use nalgebra::Vector3;
+use ocl_include::MemHook;
+use clay_core::Space;
+use crate::math;
+
+
+/// Hyperbolic space in Poincare ball model.
+pub enum Hyperbolic {}
+
+impl Space for Hyperbolic {
+    fn header() -> String {
+        "clay_hyper/space.h".to_string()
+    }
+    fn hook() -> Option<MemHook> {
+        Some(crate::source())
+    }
+    fn exp(v: &Vector3<f64>) -> Vector3<f64> {
+        math::exp(v)
+    }
+    fn contains(pos: &Vector3<f64>) -> bool {
+        pos.norm() < 1.0
+    }
+}
@@ -0,0 +1,32 @@
+use nalgebra::Vector3;
+use clay_core::{material::*, object::Covered, shape::*};
+use clay::{material::*, background::ConstantBackground, DefaultWorker};
+use crate::{
+    map::Moebius, shape::*,
+    scene::HyperScene, view::HyperView,
+};
+
+
+type TestObject = Covered<ShapeMapper<HyperBall, Moebius>, Colored<Diffuse>>;
+
+#[test]
+fn collect_sources() {
+    let builder = DefaultWorker::<HyperScene<TestObject, ConstantBackground>, HyperView>::builder().unwrap();
+    let source = builder.programs().render.source();
+    assert!(source.contains("moebius_abs_inv"));
+    assert!(source.contains("hyper_ball_hit"));
+    assert!(source.contains("float3 hyper_advance("));
+    assert!(source.contains("space_point space_advance("));
+
+    let ball = HyperBall::at(0.5, Vector3::new(0.2, 0.0, 0.0)).cover(Diffuse {}.color_with(Vector3::new(0.5, 0.5, 0.5)));
+    assert_eq!(ball.shape.map.shift, Vector3::new(0.2, 0.0, 0.0));
+}
+
+#[test]
+fn collect_tiled_sources() {
+    use crate::scene::TiledScene;
+    let builder = DefaultWorker::<TiledScene<TestObject, ConstantBackground>, HyperView>::builder().unwrap();
+    let source = builder.programs().render.source();
+    assert!(source.contains("Wall wall_load("));
+    assert!(source.contains("Ray wall_fold("));
+}
@@ -0,0 +1,132 @@
+use std::f64::consts::PI;
+use nalgebra::{Vector3, Vector4};
+use clay_core::pack::*;
+use crate::math;
+
+
+/// Plane at the hyperbolic `distance` from the origin orthogonal to the unit `normal`.
+///
+/// Walls bound the fundamental domain of a tiled scene, the other tiles
+/// are its mirror images in the walls.
+#[derive(Clone, Debug, Pack, Unpack)]
+pub struct Wall {
+    pub normal: Vector3<f64>,
+    pub distance: f64,
+}
+
+impl Wall {
+    pub fn new(normal: Vector3<f64>, distance: f64) -> Self {
+        Self { normal: normal.normalize(), distance }
+    }
+
+    /// Unit spacelike vector of the plane in hyperboloid model,
+    /// the origin is on its negative side.
+    pub fn vector(&self) -> Vector4<f64> {
+        let n = self.distance.cosh()*self.normal;
+        Vector4::new(self.distance.sinh(), n.x, n.y, n.z)
+    }
+
+    /// Reflects the point of Poincare ball model in the wall.
+    pub fn reflect(&self, x: &Vector3<f64>) -> Vector3<f64> {
+        // The wall is a sphere orthogonal to the boundary of the ball
+        let (t, s) = (self.distance.tanh(), self.distance.sinh());
+        if t == 0.0 {
+            return x - 2.0*x.dot(&self.normal)*self.normal;
+        }
+        let c = self.normal/t;
+        let d = x - c;
+        c + d/(s*s*d.norm_squared())
+    }
+}
+
+/// Minkowski inner product of hyperboloid model, the time coordinate goes first.
+pub fn minkowski(a: &Vector4<f64>, b: &Vector4<f64>) -> f64 {
+    -a.x*b.x + a.y*b.y + a.z*b.z + a.w*b.w
+}
+
+/// Regular tiling `{p, q}` of the plane `z = 0` by `p`-gons, `q` of them at each vertex.
+///
+/// The space is tiled by infinite vertical prisms over the polygons.
+/// The tiling exists in hyperbolic space only if `1/p + 1/q < 1/2`.
+/// Each polygon is made of `2*p` copies of the `(2, p, q)` triangle the walls are built from.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RegularTiling {
+    pub p: usize,
+    pub q: usize,
+}
+
+impl RegularTiling {
+    pub fn new(p: usize, q: usize) -> Self {
+        assert!(p*q > 2*(p + q), "{{{}, {}}} tiling is not hyperbolic", p, q);
+        Self { p, q }
+    }
+
+    /// Distance from the center of the polygon to its edges.
+    pub fn inradius(&self) -> f64 {
+        let (p, q) = (self.p as f64, self.q as f64);
+        ((PI/q).cos()/(PI/p).sin()).acosh()
+    }
+
+    /// Distance from the center of the polygon to its vertices.
+    pub fn circumradius(&self) -> f64 {
+        let (p, q) = (self.p as f64, self.q as f64);
+        (1.0/((PI/p).tan()*(PI/q).tan())).acosh()
+    }
+
+    /// Vertices of the central polygon in Poincare ball model.
+    pub fn vertices(&self) -> Vec<Vector3<f64>> {
+        let r = self.circumradius();
+        (0..self.p).map(|k| {
+            let angle = (2.0*k as f64 + 1.0)*PI/(self.p as f64);
+            math::exp(&(r*Vector3::new(angle.cos(), angle.sin(), 0.0)))
+        }).collect()
+    }
+
+    /// Walls of the prism over the triangle with vertices at the center of the central polygon,
+    /// the middle of its edge on the `x` axis and its first vertex.
+    ///
+    /// The walls meet at angles `pi/p`, `pi/2` and `pi/q`, so reflections in them
+    /// tile the space for any `p` and `q`. The objects of the triangle are mirrored
+    /// in its sides, so the polygons are symmetric.
+    pub fn walls(&self) -> Vec<Wall> {
+        let angle = PI/(self.p as f64);
+        vec![
+            Wall::new(Vector3::new(0.0, -1.0, 0.0), 0.0),
+            Wall::new(Vector3::new(-angle.sin(), angle.cos(), 0.0), 0.0),
+            Wall::new(Vector3::new(1.0, 0.0, 0.0), self.inradius()),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn regular() {
+        // Odd `q` requires the walls of the triangle, not of the polygon
+        for &(p, q) in [(5, 4), (7, 3), (3, 7), (4, 5)].iter() {
+            let tiling = RegularTiling::new(p, q);
+            let walls = tiling.walls();
+            let angles = [PI/(p as f64), PI/(q as f64), 0.5*PI];
+            for (i, wall) in walls.iter().enumerate() {
+                let v = wall.vector();
+                assert!((minkowski(&v, &v) - 1.0).abs() < 1e-9);
+                // Walls meet at the angles of the triangle
+                let next = walls[(i + 1) % walls.len()].vector();
+                assert!((minkowski(&v, &next) + angles[i].cos()).abs() < 1e-9);
+
+                let x = Vector3::new(0.1, -0.3, 0.2);
+                assert!((wall.reflect(&wall.reflect(&x)) - x).norm() < 1e-9);
+            }
+            let o = Vector3::zeros();
+            assert!((math::distance(&o, &walls[2].reflect(&o)) - 2.0*tiling.inradius()).abs() < 1e-9);
+
+            // The vertex lies on the walls
+            let vertex = tiling.vertices()[0];
+            for wall in walls[1..].iter() {
+                assert!((wall.reflect(&vertex) - vertex).norm() < 1e-9);
+            }
+        }
+    }
+}
@@ -0,0 +1,6 @@
+use clay::view::ModelView;
+use crate::space::Hyperbolic;
+
+
+/// Pinhole camera in hyperbolic space placed inside Poincare ball.
+pub type HyperView = ModelView<Hyperbolic>;
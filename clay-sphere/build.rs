@@ -0,0 +1,3 @@
+fn main() {
+    clay_build::embed_ocl_src();
+}
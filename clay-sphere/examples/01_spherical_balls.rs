@@ -0,0 +1,83 @@
+use std::{
+    env,
+    f64::consts::PI,
+};
+use ocl::{Platform, Device};
+use nalgebra::{Vector3, Rotation3};
+use clay_core::{
+    Context,
+    shape::*, material::*, object::Covered,
+    shape_select,
+};
+use clay::{
+    material::*, DefaultWorker,
+    background::{GradientBackground as GradBg},
+};
+use clay_sphere::{
+    map::SphereRotation, shape::*,
+    scene::SphereScene, view::SphereView,
+};
+use clay_gui::{Window};
+
+shape_select!(MyShape {
+    Ball(TB=ShapeMapper<SphereBall, SphereRotation>),
+    Great(TG=ShapeMapper<GreatSphere, SphereRotation>),
+});
+type MyObject = Covered<MyShape, Colored<Diffuse>>;
+type MyScene = SphereScene<MyObject, GradBg>;
+type MyView = SphereView;
+
+
+fn main() {
+    // Parse args to select OpenCL platform
+    let args = env::args().collect::<Vec<_>>();
+    let platform = if args.len() > 1 {
+        let platform_list = Platform::list();
+        let index = args[1].parse::<usize>().unwrap();
+        assert!(platform_list.len() > index);
+        platform_list[index]
+    } else {
+        Platform::default()
+    };
+    let device = Device::first(platform).unwrap();
+
+    let context = Context::new(platform, device).unwrap();
+    let mut worker = DefaultWorker::<MyScene, MyView>::builder().unwrap()
+    .build(&context).unwrap();
+
+    let diffuse = |r, g, b| Diffuse {}.color_with(Vector3::new(r, g, b));
+    let mut scene = SphereScene::builder(GradBg::new(
+        Vector3::new(0.8, 0.8, 0.8), Vector3::new(0.2, 0.2, 0.2),
+    ));
+    // Balls of the same size around the great circle look smaller up to the distance `pi/2`
+    // and then larger again as they approach the antipode of the camera
+    for i in 0..8 {
+        let dist = PI*(i as f64 + 0.5)/4.0;
+        scene.add(
+            MyShape::from(SphereBall::new(0.2).map(SphereRotation::translation(dist*Vector3::x())))
+            .cover(diffuse(0.9, 0.2 + 0.1*(i as f64), 0.3))
+        );
+    }
+    // Great sphere at the distance of one unit below the origin, it closes up above the camera
+    scene.add(
+        MyShape::from(GreatSphere::new().map(SphereRotation::translation(-Vector3::z())))
+        .cover(diffuse(0.5, 0.5, 0.9))
+    );
+    // Great sphere orthogonal to the previous one
+    scene.add(
+        MyShape::from(GreatSphere::new().map(SphereRotation::rotation(
+            *Rotation3::from_axis_angle(&Vector3::x_axis(), 0.5*PI).matrix(),
+        )))
+        .cover(diffuse(0.3, 0.9, 0.3))
+    );
+    let scene = scene.build(&context).unwrap();
+
+    let mut window = Window::new((1000, 800)).unwrap();
+
+    window.start(&context, |screen, pos, map, settings| {
+        worker.set_denoise(settings.denoise.clone());
+        // Euclidean camera motion is mapped to the spherical space from the origin
+        let view = SphereView::from_tangent(pos, *map.matrix());
+        worker.enqueue(screen, &scene, &view)
+    }).unwrap();
+}
@@ -0,0 +1,35 @@
+#pragma once
+
+#include <clay_core/matrix.h>
+#include <clay_core/map/map.h>
+#include <clay_sphere/sphere.h>
+
+
+// Rotation of the sphere `x -> shift (+) rotation*x`.
+//
+// Points are mapped by `abs` and `abs_inv`.
+// The `rel` and `norm` methods map vectors at the origin,
+// for vectors at other points use the points ahead (see `clay_sphere/space.h`).
+//
+// The rotation matrix is packed by columns, so it is loaded transposed.
+
+MAP_RET sphere_rotation_rel(MAP_ARGS_DEF) {
+    return matrix3_dot(matrix3_transpose(sphere_rotation_load(ibuf, fbuf).rotation), v);
+}
+
+MAP_RET sphere_rotation_abs(MAP_ARGS_DEF) {
+    return sphere_add(sphere_rotation_load(ibuf, fbuf).shift, sphere_rotation_rel(MAP_ARGS));
+}
+
+MAP_RET sphere_rotation_rel_inv(MAP_ARGS_DEF) {
+    return matrix3_dot(sphere_rotation_load(ibuf, fbuf).rotation, v);
+}
+
+MAP_RET sphere_rotation_abs_inv(MAP_ARGS_DEF) {
+    float3 shift = sphere_rotation_load(ibuf, fbuf).shift;
+    return sphere_rotation_rel_inv(MAP_ARGS_V(sphere_add(-shift, v)));
+}
+
+MAP_RET sphere_rotation_norm(MAP_ARGS_DEF) {
+    return sphere_rotation_rel(MAP_ARGS);
+}
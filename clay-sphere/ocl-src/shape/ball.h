@@ -0,0 +1,17 @@
+#pragma once
+
+#include <clay_core/ray.h>
+#include <clay_core/shape/shape.h>
+#include <clay_sphere/shape/region.h>
+
+
+// Ball of the spherical radius centered at the origin.
+SHAPE_HIT_RET sphere_ball_hit(
+    SHAPE_HIT_ARGS_DEF
+) {
+    float radius = sphere_ball_load(ibuf, fbuf).radius;
+    return sphere_shape_hit(
+        ray, (float4)(1.0f, 0.0f, 0.0f, 0.0f), cos(radius),
+        enter, exit, norm
+    );
+}
@@ -0,0 +1,24 @@
+#pragma once
+
+#include <clay_core/ray.h>
+#include <clay_core/shape/shape.h>
+#include <clay_sphere/shape/region.h>
+
+
+// Two-sided great sphere `z = 0` passing through the origin.
+//
+// Every ray crosses it twice per turn, the nearest crossing is returned.
+SHAPE_HIT_RET great_sphere_hit(
+    SHAPE_HIT_ARGS_DEF
+) {
+    float4 q = (float4)(0.0f, 0.0f, 0.0f, 1.0f);
+    // The ray hits the half-space it doesn't start in
+    if (ray.start.z > 0.0f) {
+        q = -q;
+    }
+    if (!sphere_shape_hit(ray, q, 0.0f, enter, exit, norm)) {
+        return false;
+    }
+    *exit = *enter;
+    return true;
+}
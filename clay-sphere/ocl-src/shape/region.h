@@ -0,0 +1,20 @@
+#pragma once
+
+#include <clay_core/ray.h>
+#include <clay_sphere/sphere.h>
+
+
+// Hits the region with the ray in stereographic model and returns the normal in it.
+bool sphere_shape_hit(
+    Ray ray, float4 q, float c,
+    float *enter, float *exit, float3 *norm
+) {
+    float4 p = sphere_lift(ray.start), v = sphere_lift_dir(ray.start, ray.dir);
+    float4 n;
+    if (!sphere_region_hit(p, v, q, c, enter, exit, &n)) {
+        return false;
+    }
+    float3 x = sphere_advance(ray.start, ray.dir, *enter);
+    *norm = sphere_drop_dir(x, n);
+    return true;
+}
@@ -0,0 +1,37 @@
+#pragma once
+
+#include <clay_sphere/sphere.h>
+
+// Spherical space in stereographic model, implements `clay_core/space/space.h`.
+
+typedef float3 space_point;
+typedef float3 space_vector;
+
+space_point space_advance(space_point x, space_vector d, float t) {
+    return sphere_advance(x, d, t);
+}
+
+float space_distance(space_point x, space_point y) {
+    return sphere_distance(x, y);
+}
+
+space_vector space_transport(space_point x, space_vector v, space_point y) {
+    return sphere_transport(x, v, y);
+}
+
+// Rays never go to infinity, so the background is looked up
+// by the direction of the ray transported to the origin
+space_vector space_background_dir(space_point x, space_vector d) {
+    return sphere_transport(x, d, (space_point)(0.0f));
+}
+
+// Minimal distance to hit the object the ray started from
+#define SPACE_RETURN_EPS 1e-3f
+
+// Maps must be isometries. Directions are carried as the points at the distance `pi/2` ahead,
+// so only `abs` and `abs_inv` of the map are used and distances need no rescaling.
+#define SPACE_MAP_DIR_INV(map, x, d, new_x, di, df) \
+    sphere_tangent((new_x), map##_abs_inv(MAP_ARGS_VB(sphere_ahead((x), (d)), di, df)))
+
+#define SPACE_MAP_NORM(map, x, n, new_x, di, df) \
+    sphere_tangent((new_x), map##_abs(MAP_ARGS_VB(sphere_ahead((x), normalize(n)), di, df)))
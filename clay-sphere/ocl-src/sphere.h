@@ -0,0 +1,97 @@
+#pragma once
+
+// Stereographic model of spherical space.
+//
+// The unit 3-sphere in `R^4` is projected from the pole `(-1, 0, 0, 0)` to the whole `R^3`,
+// the pole itself is mapped to infinity. The model is conformal, so the direction
+// of a ray at its start point is an ordinary unit vector and angles are Euclidean.
+// Distances along great circles are spherical, every geodesic is closed and has the length `2*pi`.
+
+
+// Spherical addition `a (+) x` - the rotation that moves the origin to `a`.
+float3 sphere_add(float3 a, float3 x) {
+    float ax = dot(a, x), aa = dot(a, a), xx = dot(x, x);
+    return ((1.0f - 2.0f*ax - xx)*a + (1.0f + aa)*x)/(1.0f - 2.0f*ax + aa*xx);
+}
+
+
+// Rays are lifted to the 3-sphere to be advanced and intersected with shapes,
+// the ray is the great circle `p*cos(t) + v*sin(t)` where `p` and `v` are orthogonal unit vectors.
+// The axis of the projection is stored in `x` component and the others in `yzw`.
+
+float4 sphere_lift(float3 x) {
+    float xx = dot(x, x);
+    return (float4)(1.0f - xx, 2.0f*x)/(1.0f + xx);
+}
+
+float4 sphere_lift_dir(float3 x, float3 d) {
+    float k = 2.0f*dot(x, d)/(1.0f + dot(x, x));
+    return (float4)(-k, d - k*x);
+}
+
+float3 sphere_drop(float4 p) {
+    return p.yzw/(1.0f + p.x);
+}
+
+float3 sphere_drop_dir(float3 x, float4 v) {
+    return v.yzw - x*v.x;
+}
+
+// Point at the distance `t` from `x` along the great circle in direction `d`.
+float3 sphere_advance(float3 x, float3 d, float t) {
+    return sphere_drop(sphere_lift(x)*cos(t) + sphere_lift_dir(x, d)*sin(t));
+}
+
+// Point at the distance `pi/2` from `x` in direction `d`.
+//
+// It is used to carry the direction through the maps of points,
+// since the lift of the point is the lifted direction itself.
+float3 sphere_ahead(float3 x, float3 d) {
+    return sphere_drop(sphere_lift_dir(x, d));
+}
+
+// Direction at `x` to the point `e` lying at the distance `pi/2` from it.
+float3 sphere_tangent(float3 x, float3 e) {
+    return normalize(sphere_drop_dir(x, sphere_lift(e)));
+}
+
+float sphere_distance(float3 x, float3 y) {
+    return acos(clamp(dot(sphere_lift(x), sphere_lift(y)), -1.0f, 1.0f));
+}
+
+// Parallel transport of the direction `v` at `x` along the great circle to `y`.
+//
+// It is the gyration `gyr[y, -x]`, the rotation left after moving `x` to `y` by spherical additions.
+float3 sphere_transport(float3 x, float3 v, float3 y) {
+    return normalize(sphere_add(-sphere_add(y, -x), sphere_add(y, sphere_add(-x, v))));
+}
+
+// Intersection of the great circle `p*cos(t) + v*sin(t)` with the region `<x, q> >= c`.
+//
+// The ray must start outside of the region. Balls and half-spaces are described this way.
+// The circle enters the region once per turn, so the distances are within `(0, 2*pi)`.
+// The returned normal is pointing outward.
+bool sphere_region_hit(
+    float4 p, float4 v, float4 q, float c,
+    float *enter, float *exit, float4 *norm
+) {
+    // Along the circle `<x, q> = r*cos(t - phi)`
+    float a = dot(p, q), b = dot(v, q);
+    if (a >= c) {
+        return false;
+    }
+    float r = sqrt(a*a + b*b);
+    if (r <= c) {
+        return false;
+    }
+    float alpha = acos(c/r), phi = atan2(b, a);
+    float t = phi - alpha;
+    t -= 2.0f*M_PI_F*floor(t/(2.0f*M_PI_F));
+    *enter = t;
+    *exit = t + 2.0f*alpha;
+
+    float4 x = p*cos(t) + v*sin(t);
+    float4 n = -(q - dot(q, x)*x);
+    *norm = normalize(n);
+    return true;
+}
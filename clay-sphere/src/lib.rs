@@ -0,0 +1,18 @@
+pub use clay_core as core;
+
+pub use clay_core::Error;
+pub use clay_core::Result;
+
+pub mod math;
+pub mod space;
+pub mod map;
+pub mod shape;
+
+pub mod scene;
+pub mod view;
+
+pub mod source;
+pub use source::*;
+
+#[cfg(test)]
+mod test;
@@ -0,0 +1,124 @@
+use std::f64::consts::PI;
+use nalgebra::{Vector3, Matrix3, Matrix4};
+use clay_core::{pack::*, class::*, map::*};
+use crate::math;
+
+
+/// Isometry of the spherical space in stereographic model, i.e. a rotation of the 3-sphere in `R^4`.
+///
+/// It rotates the space around the origin and then moves the origin to `shift`
+/// along the great circle. Any rotation of `R^4` could be represented this way.
+#[derive(Clone, Debug, Pack, Unpack, Instance)]
+#[instance(class = "MapClass", source = "clay_sphere/map/rotation.h", name = "sphere_rotation")]
+pub struct SphereRotation {
+    pub rotation: Matrix3<f64>,
+    pub shift: Vector3<f64>,
+}
+
+impl SphereRotation {
+    pub fn new(rotation: Matrix3<f64>, shift: Vector3<f64>) -> Self {
+        Self { rotation, shift }
+    }
+    pub fn identity() -> Self {
+        Self::new(Matrix3::identity(), Vector3::zeros())
+    }
+    pub fn rotation(rotation: Matrix3<f64>) -> Self {
+        Self::new(rotation, Vector3::zeros())
+    }
+    /// Moves the origin by the spherical distance of `dir` length in its direction.
+    ///
+    /// Geodesics are closed, so the length is taken modulo `2 pi`. Panics if the origin
+    /// is moved to its antipode, the point at infinity of the model.
+    pub fn translation(dir: Vector3<f64>) -> Self {
+        let len = dir.norm();
+        let reduced = (len + PI).rem_euclid(2.0*PI) - PI;
+        assert!(reduced > -PI, "translation moves the origin to the antipode");
+        let dir = if len > 0.0 { reduced*dir/len } else { dir };
+        Self::new(Matrix3::identity(), math::exp(&dir))
+    }
+
+    pub fn apply(&self, x: &Vector3<f64>) -> Vector3<f64> {
+        math::add(&self.shift, &(self.rotation*x))
+    }
+    pub fn apply_inv(&self, x: &Vector3<f64>) -> Vector3<f64> {
+        self.rotation.transpose()*math::add(&-self.shift, x)
+    }
+
+    pub fn inverse(&self) -> Self {
+        let rotation = self.rotation.transpose();
+        Self { shift: -(rotation*self.shift), rotation }
+    }
+
+    /// Isometry applying this one and then `other`.
+    pub fn then(&self, other: &SphereRotation) -> Self {
+        let shift = other.apply(&self.apply(&Vector3::zeros()));
+        // Moving the result back to the origin leaves a rotation, its columns
+        // are the images of the points at the distance `pi/2` on the axes
+        let rotation = Matrix3::from_fn(|i, j| {
+            let e = Vector3::from_fn(|k, _| if k == j { 1.0 } else { 0.0 });
+            math::add(&-shift, &other.apply(&self.apply(&e)))[i]
+        });
+        Self { rotation, shift }
+    }
+
+    /// Orthogonal matrix of the rotation of `R^4` acting on the points of `math::lift`.
+    pub fn matrix(&self) -> Matrix4<f64> {
+        // Points at the distance `pi/2` from the origin coincide with tangent vectors there
+        let mut m = Matrix4::zeros();
+        m.set_column(0, &math::lift(&self.shift));
+        for j in 0..3 {
+            let e = Vector3::from_fn(|k, _| if k == j { 1.0 } else { 0.0 });
+            m.set_column(j + 1, &math::lift(&self.apply(&e)));
+        }
+        m
+    }
+}
+
+impl Map for SphereRotation {}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use nalgebra::Rotation3;
+
+    #[test]
+    fn compose() {
+        let a = SphereRotation::new(
+            *Rotation3::from_euler_angles(0.1, 0.2, 0.3).matrix(),
+            Vector3::new(0.2, -0.3, 1.1),
+        );
+        let b = SphereRotation::translation(Vector3::new(0.0, 1.0, 2.0));
+        let x = Vector3::new(-0.4, 0.3, 0.2);
+        assert!((a.then(&b).apply(&x) - b.apply(&a.apply(&x))).norm() < 1e-9);
+        assert!((a.inverse().apply(&a.apply(&x)) - x).norm() < 1e-9);
+        assert!((a.apply_inv(&a.apply(&x)) - x).norm() < 1e-9);
+    }
+
+    #[test]
+    fn translation_period() {
+        let x = Vector3::new(0.3, -0.5, 0.2);
+        let a = SphereRotation::translation(15.0*PI/8.0*Vector3::x());
+        let b = SphereRotation::translation(-PI/8.0*Vector3::x());
+        assert!((a.apply(&x) - b.apply(&x)).norm() < 1e-9);
+        let c = SphereRotation::translation((2.0*PI + 0.5)*Vector3::y());
+        assert!((math::distance(&Vector3::zeros(), &c.shift) - 0.5).abs() < 1e-9);
+    }
+
+    #[test]
+    #[should_panic]
+    fn translation_antipode() {
+        SphereRotation::translation(PI*Vector3::z());
+    }
+
+    #[test]
+    fn matrix() {
+        let a = SphereRotation::new(
+            *Rotation3::from_euler_angles(-0.3, 0.5, 0.1).matrix(),
+            Vector3::new(0.7, 0.4, -0.9),
+        );
+        let m = a.matrix();
+        let x = Vector3::new(0.3, -1.2, 0.5);
+        assert!((m.transpose()*m - Matrix4::identity()).norm() < 1e-9);
+        assert!((m*math::lift(&x) - math::lift(&a.apply(&x))).norm() < 1e-9);
+    }
+}
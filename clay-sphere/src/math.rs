@@ -0,0 +1,94 @@
+//! Host-side operations in the stereographic model of the 3-sphere, same as in `clay_sphere/sphere.h`.
+
+use nalgebra::{Vector3, Vector4};
+
+
+/// Spherical addition `a (+) x` - the rotation of the sphere that moves the origin to `a`
+/// along the great circle passing through them.
+pub fn add(a: &Vector3<f64>, x: &Vector3<f64>) -> Vector3<f64> {
+    let (ax, aa, xx) = (a.dot(x), a.dot(a), x.dot(x));
+    ((1.0 - 2.0*ax - xx)*a + (1.0 + aa)*x)/(1.0 - 2.0*ax + aa*xx)
+}
+
+/// Point of the unit 3-sphere in `R^4` projected to `x`.
+///
+/// The `x` component of the result is the axis of the projection,
+/// the origin is mapped to `(1, 0, 0, 0)`.
+pub fn lift(x: &Vector3<f64>) -> Vector4<f64> {
+    let xx = x.norm_squared();
+    let y = 2.0*x;
+    Vector4::new(1.0 - xx, y.x, y.y, y.z)/(1.0 + xx)
+}
+
+/// Stereographic projection of the point of the unit 3-sphere.
+pub fn drop(p: &Vector4<f64>) -> Vector3<f64> {
+    Vector3::new(p.y, p.z, p.w)/(1.0 + p.x)
+}
+
+/// Point at the distance `t` from `x` along the great circle in the direction `dir`.
+pub fn advance(x: &Vector3<f64>, dir: &Vector3<f64>, t: f64) -> Vector3<f64> {
+    add(x, &((0.5*t).tan()*dir.normalize()))
+}
+
+/// Spherical distance between two points, it is never greater than `pi`.
+pub fn distance(x: &Vector3<f64>, y: &Vector3<f64>) -> f64 {
+    let chord = 2.0*(x - y).norm()/((1.0 + x.norm_squared())*(1.0 + y.norm_squared())).sqrt();
+    2.0*(0.5*chord).min(1.0).asin()
+}
+
+/// Parallel transport of the vector `v` at `x` along the great circle to `y`.
+///
+/// It is the gyration `gyr[y, -x]` that preserves Euclidean length,
+/// so it is valid for the directions of the conformal model.
+pub fn transport(x: &Vector3<f64>, v: &Vector3<f64>, y: &Vector3<f64>) -> Vector3<f64> {
+    add(&-add(y, &-x), &add(y, &add(&-x, v)))
+}
+
+/// Maps the vector of the tangent space at the origin to the point
+/// at the distance of the vector length in its direction.
+///
+/// The length should be less than `pi`, the farthest point is projected to infinity.
+pub fn exp(v: &Vector3<f64>) -> Vector3<f64> {
+    let len = v.norm();
+    if len > 0.0 {
+        (0.5*len).tan()*v/len
+    } else {
+        Vector3::zeros()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::f64::consts::PI;
+
+    #[test]
+    fn isometry() {
+        let (a, x, y) = (
+            Vector3::new(0.3, -0.2, 1.5),
+            Vector3::new(-0.6, 0.1, 0.2),
+            Vector3::new(1.1, 0.7, -0.4),
+        );
+        assert!((distance(&add(&a, &x), &add(&a, &y)) - distance(&x, &y)).abs() < 1e-9);
+        assert!((add(&-a, &add(&a, &x)) - x).norm() < 1e-9);
+        assert!((distance(&Vector3::zeros(), &exp(&(2.0*Vector3::x()))) - 2.0).abs() < 1e-9);
+        assert!((drop(&lift(&x)) - x).norm() < 1e-9);
+
+        // Geodesics are closed
+        let z = advance(&x, &Vector3::y(), 2.0*PI - 1e-3);
+        assert!(distance(&x, &z) < 2e-3);
+    }
+
+    #[test]
+    fn transport_along_geodesic() {
+        let (x, y) = (Vector3::new(0.5, -1.3, 0.1), Vector3::new(-0.4, 0.9, 0.3));
+        // Tangent of the great circle is transported to the tangent
+        let (tx, ty) = (add(&-x, &y).normalize(), -add(&-y, &x).normalize());
+        assert!((transport(&x, &tx, &y) - ty).norm() < 1e-9);
+        // Angles are preserved
+        let v = Vector3::new(0.3, 0.5, -0.2).normalize();
+        let w = transport(&x, &v, &y);
+        assert!((w.norm() - 1.0).abs() < 1e-9);
+        assert!((w.dot(&ty) - v.dot(&tx)).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,10 @@
+use clay::scene::ListScene;
+use crate::space::Spherical;
+
+/// List of objects in spherical space.
+///
+/// Rays travel along great circles of stereographic model and may hit objects
+/// behind the camera after going around the space. A ray that hits nothing
+/// returns to its start, so the background is looked up by the ray direction
+/// transported to the origin.
+pub type SphereScene<O, B> = ListScene<O, B, Spherical>;
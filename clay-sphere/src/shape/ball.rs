@@ -0,0 +1,25 @@
+use nalgebra::{Vector3, Matrix3};
+use clay_core::{pack::*, class::*, shape::*};
+use crate::map::SphereRotation;
+
+
+/// Ball of the spherical `radius` centered at the origin.
+///
+/// The radius should be less than `pi`, the ball of radius `pi/2` is a hemisphere.
+#[derive(Clone, Debug, Pack, Unpack, Instance)]
+#[instance(class = "ShapeClass", source = "clay_sphere/shape/ball.h", name = "sphere_ball")]
+pub struct SphereBall {
+    pub radius: f64,
+}
+
+impl SphereBall {
+    pub fn new(radius: f64) -> Self {
+        Self { radius }
+    }
+    /// Ball centered at the point of stereographic model.
+    pub fn at(radius: f64, pos: Vector3<f64>) -> ShapeMapper<Self, SphereRotation> {
+        Self::new(radius).map(SphereRotation::new(Matrix3::identity(), pos))
+    }
+}
+
+impl Shape for SphereBall {}
@@ -0,0 +1,18 @@
+use clay_core::{pack::*, class::*, shape::*};
+
+
+/// Two-sided totally geodesic sphere `z = 0`.
+///
+/// It divides the space into two equal halves and has the largest area among the spheres.
+/// Other great spheres are obtained by moving it with isometries.
+#[derive(Clone, Debug, Default, Pack, Unpack, Instance)]
+#[instance(class = "ShapeClass", source = "clay_sphere/shape/great_sphere.h", name = "great_sphere")]
+pub struct GreatSphere {}
+
+impl GreatSphere {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl Shape for GreatSphere {}
@@ -0,0 +1,4 @@
+mod ball;
+pub use ball::SphereBall;
+mod great_sphere;
+pub use great_sphere::GreatSphere;
@@ -0,0 +1,9 @@
+use ocl_include::{MemHook};
+use clay_core::embedded_hook;
+
+include!(concat!(env!("OUT_DIR"), "/ocl_src_list.rs"));
+
+
+pub fn source() -> MemHook {
+    embedded_hook("clay_sphere", &OCL_SRC_LIST)
+}
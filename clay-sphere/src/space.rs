@@ -0,0 +1,20 @@
+use nalgebra::Vector3;
+use ocl_include::MemHook;
+use clay_core::Space;
+use crate::math;
+
+
+/// Spherical space in stereographic model.
+pub enum Spherical {}
+
+impl Space for Spherical {
+    fn header() -> String {
+        "clay_sphere/space.h".to_string()
+    }
+    fn hook() -> Option<MemHook> {
+        Some(crate::source())
+    }
+    fn exp(v: &Vector3<f64>) -> Vector3<f64> {
+        math::exp(v)
+    }
+}
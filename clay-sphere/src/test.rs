@@ -0,0 +1,23 @@
+use nalgebra::Vector3;
+use clay_core::{material::*, object::Covered, shape::*};
+use clay::{material::*, background::ConstantBackground, DefaultWorker};
+use crate::{
+    map::SphereRotation, shape::*,
+    scene::SphereScene, view::SphereView,
+};
+
+
+type TestObject = Covered<ShapeMapper<SphereBall, SphereRotation>, Colored<Diffuse>>;
+
+#[test]
+fn collect_sources() {
+    let builder = DefaultWorker::<SphereScene<TestObject, ConstantBackground>, SphereView>::builder().unwrap();
+    let source = builder.programs().render.source();
+    assert!(source.contains("sphere_rotation_abs_inv"));
+    assert!(source.contains("sphere_ball_hit"));
+    assert!(source.contains("float3 sphere_advance("));
+    assert!(source.contains("space_point space_advance("));
+
+    let ball = SphereBall::at(0.5, Vector3::new(2.0, 0.0, 0.0)).cover(Diffuse {}.color_with(Vector3::new(0.5, 0.5, 0.5)));
+    assert_eq!(ball.shape.map.shift, Vector3::new(2.0, 0.0, 0.0));
+}
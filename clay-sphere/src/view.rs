@@ -0,0 +1,8 @@
+use clay::view::ModelView;
+use crate::space::Spherical;
+
+
+/// Pinhole camera in spherical space placed at the point of stereographic model.
+///
+/// Moving by `2*pi` in any direction returns the camera to the origin.
+pub type SphereView = ModelView<Spherical>;
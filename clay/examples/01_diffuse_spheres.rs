@@ -2,13 +2,12 @@ use std::env;
 use ocl::{Platform, Device};
 use nalgebra::{Vector3};
 use clay_core::{
-    Context,
+    Context, Worker, Aov,
     shape::*, material::*, object::Covered,
 };
 use clay::{
     scene::ListScene, view::ProjView,
     shape::*, material::*,
-    worker::DefaultWorker,
     background::{GradientBackground as GradBg},
 };
 use clay_gui::{Window};
@@ -35,16 +34,20 @@ fn main() {
     let device = Device::first(platform).unwrap();
 
     let context = Context::new(platform, device).unwrap();
-    let worker = DefaultWorker::<MyScene, MyView>::builder().unwrap();
+    let mut worker = Worker::<MyScene, MyView>::builder();
+    worker.add_hook(clay::source());
+    // Object indices are used to highlight the picked object
+    worker.aovs(&[Aov::ObjectId]);
+    let worker = worker.collect().unwrap();
 
     // Print build log
     let mut worker = worker.build(&context).unwrap();
-    for (name, msg) in [
+    for (name, diagnostics) in [
         ("render", &worker.programs().render.1),
         ("draw", &worker.programs().draw.1),
     ].iter() {
-        if msg.len() > 0 {
-            println!("'{}' build log:\n{}", name, msg);
+        for diagnostic in diagnostics.iter() {
+            println!("'{}': {}", name, diagnostic);
         }
     }
 
@@ -59,16 +62,20 @@ fn main() {
         Sphere::new(1.0, Vector3::new(1.0, 0.0, 0.0))
         .cover(Diffuse {}.color_with(Vector3::new(0.3, 0.3, 0.9)))
     );
-    let scene = scene.build(&context).unwrap();
+    let mut scene = scene.build(&context).unwrap();
 
 
     let mut window = Window::new((1000, 800)).unwrap();
+    window.aovs(&[Aov::ObjectId]);
 
-    window.start(&context, |screen, pos, map| {
+    window.start(&context, |screen, pos, map, settings| {
+        worker.set_denoise(settings.denoise.clone());
         let view = ProjView {
             pos: pos + Vector3::new(0.0, -2.0, 0.0),
             ori: map.matrix().clone(),
         };
-        worker.render(screen, &scene, &view)
+        // Objects are picked by right click and edited with keys
+        settings.picking.apply(&mut scene, &view, screen.dims())?;
+        worker.enqueue(screen, &scene, &view)
     }).unwrap();
 }
@@ -0,0 +1,67 @@
+use std::{
+    fs::File,
+    io::Write,
+    time::{Duration, Instant},
+};
+use nalgebra::{Vector3, Matrix3};
+use clay_core::{
+    MultiContext, buffer::MultiScreen,
+    shape::*, material::*, object::Covered,
+};
+use clay::{
+    scene::ListScene, view::ProjView,
+    shape::*, material::*,
+    worker::DefaultWorker,
+    background::{GradientBackground as GradBg},
+};
+
+type MyObject = Covered<Sphere, Colored<Diffuse>>;
+
+type MyScene = ListScene<MyObject, GradBg>;
+type MyView = ProjView;
+
+
+fn main() {
+    // Use all devices of all platforms
+    let context = MultiContext::all().unwrap();
+    for ctx in context.contexts() {
+        println!("Device: {}", ctx.device().name().unwrap());
+    }
+
+    let mut worker = DefaultWorker::<MyScene, MyView>::builder().unwrap()
+    .build_multi(&context).unwrap();
+
+    // Buffers are bound to the context, so the scene is created for each device
+    let scenes = context.contexts().iter().map(|ctx| {
+        let mut scene = ListScene::builder(GradBg::new(
+            Vector3::new(0.8, 0.8, 0.8), Vector3::new(0.2, 0.2, 0.2),
+        ));
+        scene.add(
+            Sphere::new(0.75, Vector3::new(-0.75, 0.0, 0.0))
+            .cover(Diffuse {}.color_with(Vector3::new(0.3, 0.9, 0.3)))
+        );
+        scene.add(
+            Sphere::new(1.0, Vector3::new(1.0, 0.0, 0.0))
+            .cover(Diffuse {}.color_with(Vector3::new(0.3, 0.3, 0.9)))
+        );
+        scene.build(ctx).unwrap()
+    }).collect::<Vec<_>>();
+
+    let view = ProjView {
+        pos: Vector3::new(0.0, -2.0, 0.0),
+        ori: Matrix3::identity(),
+    };
+
+    let dims = (800, 600);
+    let mut screen = MultiScreen::new(&context, dims).unwrap();
+
+    let start = Instant::now();
+    while start.elapsed() < Duration::from_secs(5) {
+        worker.render(&mut screen, &scenes, &view).unwrap();
+    }
+    println!("Passes: {}, rates: {:?}", screen.n_passes(), worker.rates());
+
+    let mut file = File::create("__gen_multi_device.ppm").unwrap();
+    write!(file, "P6\n{} {}\n255\n", dims.0, dims.1).unwrap();
+    file.write_all(&screen.read().unwrap()).unwrap();
+}
@@ -0,0 +1,72 @@
+use std::{
+    env,
+    fs::File,
+    io::Write,
+};
+use ocl::{Platform, Device};
+use nalgebra::{Vector3, Matrix3};
+use clay_core::{
+    Context, Screen, Adaptive,
+    shape::*, material::*, object::Covered,
+};
+use clay::{
+    scene::ListScene, view::ProjView,
+    shape::*, material::*,
+    worker::DefaultWorker,
+    background::{GradientBackground as GradBg},
+};
+
+type MyObject = Covered<Sphere, Colored<Diffuse>>;
+
+type MyScene = ListScene<MyObject, GradBg>;
+type MyView = ProjView;
+
+
+fn main() {
+    // Parse args to select OpenCL platform
+    let args = env::args().collect::<Vec<_>>();
+    let platform = if args.len() > 1 {
+        let platform_list = Platform::list();
+        let index = args[1].parse::<usize>().unwrap();
+        assert!(platform_list.len() > index);
+        platform_list[index]
+    } else {
+        Platform::default()
+    };
+    let device = Device::first(platform).unwrap();
+
+    let context = Context::new(platform, device).unwrap();
+    let mut worker = DefaultWorker::<MyScene, MyView>::builder().unwrap()
+    .build(&context).unwrap();
+    // Stop rendering a tile when all its pixels converge
+    worker.set_adaptive(Adaptive { enabled: true, ..Adaptive::default() });
+
+    let mut scene = ListScene::builder(GradBg::new(
+        Vector3::new(0.8, 0.8, 0.8), Vector3::new(0.2, 0.2, 0.2),
+    ));
+    scene.add(
+        Sphere::new(0.75, Vector3::new(-0.75, 0.0, 0.0))
+        .cover(Diffuse {}.color_with(Vector3::new(0.3, 0.9, 0.3)))
+    );
+    scene.add(
+        Sphere::new(1.0, Vector3::new(1.0, 0.0, 0.0))
+        .cover(Diffuse {}.color_with(Vector3::new(0.3, 0.3, 0.9)))
+    );
+    let scene = scene.build(&context).unwrap();
+
+    let view = ProjView {
+        pos: Vector3::new(0.0, -2.0, 0.0),
+        ori: Matrix3::identity(),
+    };
+
+    // Only the tile-sized buffers are allocated on the device
+    let frame = (8000, 6000);
+    let mut screen = Screen::new(&context, (1024, 1024)).unwrap();
+    let image = worker.render_tiled(&mut screen, frame, 64, &scene, &view, |done, total| {
+        println!("Tile {}/{}", done, total);
+    }).unwrap();
+
+    let mut file = File::create("__gen_tiled_render.ppm").unwrap();
+    write!(file, "P6\n{} {}\n255\n", frame.0, frame.1).unwrap();
+    file.write_all(&image).unwrap();
+}
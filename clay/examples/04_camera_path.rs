@@ -0,0 +1,84 @@
+use std::{
+    env,
+    f64::consts::PI,
+};
+use ocl::{Platform, Device};
+use nalgebra::{Vector3, Rotation3};
+use clay_core::{
+    Context, Screen, Track, Camera, Interpolation,
+    shape::*, material::*, object::Covered,
+};
+use clay::{
+    scene::ListScene, view::ProjView,
+    shape::*, material::*,
+    worker::DefaultWorker,
+    background::{GradientBackground as GradBg},
+};
+
+type MyObject = Covered<Sphere, Colored<Diffuse>>;
+
+type MyScene = ListScene<MyObject, GradBg>;
+type MyView = ProjView;
+
+
+fn main() {
+    // Parse args to select OpenCL platform and the camera track recorded in GUI
+    let args = env::args().collect::<Vec<_>>();
+    let platform = if args.len() > 1 {
+        let platform_list = Platform::list();
+        let index = args[1].parse::<usize>().unwrap();
+        assert!(platform_list.len() > index);
+        platform_list[index]
+    } else {
+        Platform::default()
+    };
+    let device = Device::first(platform).unwrap();
+
+    let track = match args.get(2) {
+        Some(path) => Track::<Camera>::load(path).unwrap(),
+        None => {
+            // Fly around the spheres looking at the center
+            let mut track = Track::new(Interpolation::CatmullRom);
+            for i in 0..5 {
+                let angle = 0.5*PI*(i as f64);
+                let ori = Rotation3::from_axis_angle(&Vector3::z_axis(), angle)
+                    *Rotation3::from_axis_angle(&Vector3::x_axis(), 0.5*PI);
+                track.add(i as f64, Camera::new(ori*Vector3::new(0.0, 0.0, 4.0), ori));
+            }
+            track
+        },
+    };
+
+    let context = Context::new(platform, device).unwrap();
+    let mut worker = DefaultWorker::<MyScene, MyView>::builder().unwrap()
+    .build(&context).unwrap();
+
+    let mut scene = ListScene::builder(GradBg::new(
+        Vector3::new(0.8, 0.8, 0.8), Vector3::new(0.2, 0.2, 0.2),
+    ));
+    scene.add(
+        Sphere::new(0.75, Vector3::new(-0.75, 0.0, 0.0))
+        .cover(Diffuse {}.color_with(Vector3::new(0.3, 0.9, 0.3)))
+    );
+    scene.add(
+        Sphere::new(1.0, Vector3::new(1.0, 0.0, 0.0))
+        .cover(Diffuse {}.color_with(Vector3::new(0.3, 0.3, 0.9)))
+    );
+    let mut scene = scene.build(&context).unwrap();
+
+    // 24 frames per second of the track
+    let (start, end) = track.range().unwrap();
+    let n_frames = ((end - start)*24.0).round() as usize + 1;
+    let times = (0..n_frames).map(|i| start + (i as f64)/24.0).collect::<Vec<_>>();
+
+    let mut screen = Screen::new(&context, (640, 480)).unwrap();
+    let paths = worker.render_sequence(
+        &mut screen, &mut scene, &times, 16, "__gen_camera_path",
+        |time, _scene| {
+            let camera = track.sample(time).unwrap();
+            println!("Frame at {:.3} s", time);
+            Ok(ProjView { pos: camera.pos, ori: camera.map().into_inner() })
+        },
+    ).unwrap();
+    println!("{} frames saved", paths.len());
+}
@@ -1,12 +1,10 @@
 use std::{
     env,
-    io::Write,
-    fs::{File, create_dir_all},
 };
 use ocl::{Platform, Device};
 use nalgebra::{Vector3, Matrix3};
 use clay_core::{
-    Context, Worker,
+    Context, Worker, Aov,
     shape::*, material::*, object::Covered,
     shape_select, material_select, material_combine,
 };
@@ -47,28 +45,27 @@ fn main() {
     };
     let device = Device::first(platform).unwrap();
 
-    let context = Context::new(platform, device).unwrap();
+    // Enable profiling to measure kernel execution time
+    let context = Context::with_profiling(platform, device).unwrap();
     let mut builder = Worker::<MyScene, MyView>::builder();
     builder.add_hook(clay_core::source());
     builder.add_hook(clay::source());
+    builder.dump_dir(Some("./__gen_programs"));
+    // Feature buffers guiding the denoiser
+    let aovs = [Aov::Depth, Aov::Normal, Aov::Albedo];
+    builder.aovs(&aovs);
     let builder = builder.collect().unwrap();
 
-    create_dir_all("./__gen_programs").unwrap();
-    for (name, prog) in [
-        ("render", &builder.programs().render),
-        ("draw", &builder.programs().draw),
-    ].iter() {
-        File::create(&format!("__gen_programs/{}.c", name)).unwrap()
-        .write_all(prog.source().as_bytes()).unwrap();
-    }
-
-    let mut worker = builder.build(&context).unwrap();
-    for (name, msg) in [
+    let mut worker = match builder.build(&context) {
+        Ok(worker) => worker,
+        Err(e) => panic!("{}", e),
+    };
+    for (name, diagnostics) in [
         ("render", &worker.programs().render.1),
         ("draw", &worker.programs().draw.1),
     ].iter() {
-        if msg.len() > 0 {
-            println!("'{}' build log:\n{}", name, msg);
+        for diagnostic in diagnostics.iter() {
+            println!("'{}': {}", name, diagnostic);
         }
     }
 
@@ -135,12 +132,21 @@ fn main() {
 
 
     let mut window = Window::new((1000, 800)).unwrap();
+    window.aovs(&aovs);
+    // Render at half resolution for the interactive preview
+    window.render_scale(0.5);
+    window.settings_mut().denoise.enabled = true;
 
-    window.start(&context, |screen, pos, map| {
+    worker.collect_stats(true);
+    window.start(&context, |screen, pos, map, settings| {
+        worker.set_denoise(settings.denoise.clone());
         let view = ProjView {
             pos: pos + Vector3::new(0.0, -2.0, 1.0),
             ori: map.matrix().clone(),
         };
-        worker.render(screen, &scene, &view)
+        worker.enqueue(screen, &scene, &view)
     }).unwrap();
+
+    let stats = worker.stats().unwrap().unwrap();
+    stats.write_json(&mut std::io::stdout()).unwrap();
 }
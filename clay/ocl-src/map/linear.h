@@ -5,7 +5,7 @@
 
 
 MAP_RET linear_rel(MAP_ARGS_DEF) {
-    matrix3 linear = matrix3_load(fbuf);
+    matrix3 linear = linear_load(ibuf, fbuf).linear;
     return matrix3_dot(linear, v);
 }
 
@@ -14,7 +14,7 @@ MAP_RET linear_abs(MAP_ARGS_DEF) {
 }
 
 MAP_RET linear_rel_inv(MAP_ARGS_DEF) {
-    matrix3 inverse = matrix3_load(fbuf + 9);
+    matrix3 inverse = linear_load(ibuf, fbuf).inverse;
     return matrix3_dot(inverse, v);
 }
 
@@ -23,6 +23,6 @@ MAP_RET linear_abs_inv(MAP_ARGS_DEF) {
 }
 
 MAP_RET linear_norm(MAP_ARGS_DEF) {
-    matrix3 inverse = matrix3_load(fbuf + 9);
+    matrix3 inverse = linear_load(ibuf, fbuf).inverse;
     return matrix3_dot(matrix3_transpose(inverse), v);
 }
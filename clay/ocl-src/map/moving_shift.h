@@ -0,0 +1,29 @@
+#pragma once
+
+#include <clay_core/map/map.h>
+
+
+float3 moving_shift_offset(float t, __global const int *ibuf, __global const float *fbuf) {
+    MovingShift shift = moving_shift_load(ibuf, fbuf);
+    return mix(shift.start, shift.end, clamp(t, 0.0f, 1.0f));
+}
+
+MAP_RET moving_shift_rel(MAP_ARGS_DEF) {
+    return v;
+}
+
+MAP_RET moving_shift_abs(MAP_ARGS_DEF) {
+    return v + moving_shift_offset(t, ibuf, fbuf);
+}
+
+MAP_RET moving_shift_rel_inv(MAP_ARGS_DEF) {
+    return v;
+}
+
+MAP_RET moving_shift_abs_inv(MAP_ARGS_DEF) {
+    return v - moving_shift_offset(t, ibuf, fbuf);
+}
+
+MAP_RET moving_shift_norm(MAP_ARGS_DEF) {
+    return v;
+}
@@ -4,7 +4,7 @@
 
 
 MAP_RET scale_rel(MAP_ARGS_DEF) {
-    return v*fbuf[0];
+    return v*scale_load(ibuf, fbuf).factor;
 }
 
 MAP_RET scale_abs(MAP_ARGS_DEF) {
@@ -12,7 +12,7 @@ MAP_RET scale_abs(MAP_ARGS_DEF) {
 }
 
 MAP_RET scale_rel_inv(MAP_ARGS_DEF) {
-    return v/fbuf[0];
+    return v/scale_load(ibuf, fbuf).factor;
 }
 
 MAP_RET scale_abs_inv(MAP_ARGS_DEF) {
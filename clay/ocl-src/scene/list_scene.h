@@ -1,6 +1,8 @@
 #pragma once
 
 #include <clay_core/random.h>
+#include <clay_core/trace.h>
+#include <clay_core/space/space.h>
 
 
 #define SCENE_ARGS_DEF \
@@ -29,6 +31,7 @@ bool scene_trace(
     Ray ray,
     Ray *new_ray,
     float3 *color,
+    TraceInfo *info,
     SCENE_ARGS_DEF
 ) {
     int hit_idx = -1;
@@ -41,13 +44,21 @@ bool scene_trace(
         float enter, exit;
         float3 norm;
 
+#ifndef SPACE_RETURN_EPS
         if (ray.origin == i) {
             continue;
         }
-        
+#endif
+
         __global const int *ibuf = object_buffer_int + object_size_int*i;
         __global const float *fbuf = object_buffer_float + object_size_float*i;
         if (__object_hit(seed, ray, ibuf, fbuf, &enter, &exit, &norm)) {
+#ifdef SPACE_RETURN_EPS
+            // The object the ray started from could be hit again after going around the space
+            if (ray.origin == i && enter < SPACE_RETURN_EPS) {
+                continue;
+            }
+#endif
             if (enter < hit_enter) {
                 hit_enter = enter;
                 hit_exit = exit;
@@ -58,29 +69,39 @@ bool scene_trace(
     }
     
     if (hit_idx >= 0) {
-        float3 hit_pos = ray.start + ray.dir*hit_enter;
+        // The point is found along the geodesic, the depth is the distance to it from the ray start
+        space_point hit_pos = space_advance(ray.start, ray.dir, hit_enter);
+        trace_info_hit(info, space_distance(ray.start, hit_pos), hit_norm, hit_idx);
 
         __global const int *ibuf = object_buffer_int + object_size_int*hit_idx;
         __global const float *fbuf = object_buffer_float + object_size_float*hit_idx;
-        if(__object_bounce(
-            seed, ray, hit_pos, hit_norm,
+        float3 prev_color = *color;
+        bool bounce = __object_bounce(
+            seed, ray, hit_pos, normalize(hit_norm),
             false, (float3)(0.0f), 0.0f,
             ibuf, fbuf, new_ray, color
-        )) {
+        );
+        // Emitted color is used as albedo if the material doesn't reflect
+        trace_info_albedo(info, bounce ? new_ray->color : *color - prev_color);
+        if (bounce) {
             new_ray->origin = hit_idx;
+            new_ray->time = ray.time;
             return true;
         }
         return false;
     }
 
-    // Background
-    *color += __background(ray, BACKGROUND_ARGS);
+    // Background direction is given by the space
+    Ray bg_ray = ray;
+    bg_ray.dir = space_background_dir(ray.start, ray.dir);
+    *color += __background(bg_ray, BACKGROUND_ARGS);
     return false;
 }
 
 float3 __scene_trace(
     uint *seed,
     Ray ray,
+    TraceInfo *info,
     SCENE_ARGS_DEF
 ) {
     float3 color = (float3)(0.0f);
@@ -88,7 +109,8 @@ float3 __scene_trace(
     Ray current_ray = ray;
     for (i = 0; i < MAX_DEPTH; ++i) {
         Ray next_ray = ray_new();
-        bool bounce = scene_trace(seed, current_ray, &next_ray, &color, SCENE_ARGS);
+        info->rays += 1;
+        bool bounce = scene_trace(seed, current_ray, &next_ray, &color, info, SCENE_ARGS);
         if (!bounce) {
             break;
         }
@@ -1,6 +1,8 @@
 #pragma once
 
 #include <clay_core/random.h>
+#include <clay_core/trace.h>
+#include <clay_core/space/space.h>
 
 
 #define SCENE_ARGS_DEF \
@@ -37,17 +39,13 @@
 
 #define TARGET_THRESHOLD 0.1f
 
-#define OBJ_DI 1
-#define OBJ_DF 0
-#define TAR_DI 1
-#define TAR_DF 1
-
 
 bool scene_trace(
     uint *seed,
     Ray ray,
     Ray *new_ray,
     float3 *color,
+    TraceInfo *info,
     SCENE_ARGS_DEF
 ) {
     int hit_idx = -1;
@@ -69,7 +67,7 @@ bool scene_trace(
         __global const float *fbuf = object_buffer_float + object_size_float*i;
         if (__object_hit(
             seed, ray,
-            ibuf + OBJ_DI, fbuf + OBJ_DF,
+            ibuf + OBJECT_DATA_OBJECT_DI, fbuf + OBJECT_DATA_OBJECT_DF,
             &enter, &exit, &norm
         )) {
             if (enter < hit_enter) {
@@ -77,7 +75,7 @@ bool scene_trace(
                 hit_exit = exit;
                 hit_norm = norm;
                 hit_idx = i;
-                tar_idx = ibuf[0];
+                tar_idx = object_data_load(ibuf, fbuf).target_index;
             }
         }
     }
@@ -93,7 +91,8 @@ bool scene_trace(
             }
         }
 
-        float3 hit_pos = ray.start + ray.dir*hit_enter;
+        space_point hit_pos = space_advance(ray.start, ray.dir, hit_enter);
+        trace_info_hit(info, hit_enter, hit_norm, hit_idx);
 
         // Sample target
         int target = -1;
@@ -105,11 +104,10 @@ bool scene_trace(
             __global const int *tibuf = target_buffer_int + target_size_int*target_idx;
             __global const float *tfbuf = target_buffer_float + target_size_float*target_idx;
 
-            //float brightness = tfbuf[0];
-            target = tibuf[0];
+            target = target_data_load(tibuf, tfbuf).object_index;
             target_size = __target_sample(
                 seed, hit_pos,
-                tibuf + TAR_DI, tfbuf + TAR_DF,
+                tibuf + TARGET_DATA_TARGET_DI, tfbuf + TARGET_DATA_TARGET_DF,
                 &target_dir
             );
             directed = true;
@@ -118,13 +116,17 @@ bool scene_trace(
         // Bounce from material
         __global const int *ibuf = object_buffer_int + object_size_int*hit_idx;
         __global const float *fbuf = object_buffer_float + object_size_float*hit_idx;
+        float3 prev_color = *color;
         bool bounce = __object_bounce(
             seed, ray, hit_pos, hit_norm,
             directed, target_dir, target_size,
-            ibuf + OBJ_DI, fbuf + OBJ_DF, new_ray, color
+            ibuf + OBJECT_DATA_OBJECT_DI, fbuf + OBJECT_DATA_OBJECT_DF, new_ray, color
         );
+        // Emitted color is used as albedo if the material doesn't reflect
+        trace_info_albedo(info, bounce ? new_ray->color : *color - prev_color);
         if (bounce && !(ray.history & RAY_TARGETED)) {
             new_ray->origin = hit_idx;
+            new_ray->time = ray.time;
             if (directed) {
                 new_ray->target = target;
                 new_ray->history |= RAY_TARGETED;
@@ -145,6 +147,7 @@ bool scene_trace(
 float3 __scene_trace(
     uint *seed,
     Ray ray,
+    TraceInfo *info,
     SCENE_ARGS_DEF
 ) {
     float3 color = (float3)(0.0f);
@@ -152,7 +155,8 @@ float3 __scene_trace(
     Ray current_ray = ray;
     for (i = 0; i < MAX_DEPTH; ++i) {
         Ray next_ray = ray_new();
-        bool bounce = scene_trace(seed, current_ray, &next_ray, &color, SCENE_ARGS);
+        info->rays += 1;
+        bool bounce = scene_trace(seed, current_ray, &next_ray, &color, info, SCENE_ARGS);
         if (!bounce) {
             break;
         }
@@ -16,3 +16,6 @@ pub use worker::*;
 
 pub mod source;
 pub use source::*;
+
+#[cfg(test)]
+mod test;
@@ -1,8 +1,9 @@
-use std::collections::HashSet;
 use nalgebra::{Matrix3};
-use clay_core::{pack::*, class::*, map::*};
+use clay_core::{pack::*, class::*, map::*, layout::*};
 
 
+#[derive(Instance)]
+#[instance(class = "MapClass", source = "clay/map/linear.h", name = "linear")]
 pub struct Linear(pub Matrix3<f64>);
 
 impl From<Matrix3<f64>> for Linear {
@@ -13,15 +14,6 @@ impl From<Matrix3<f64>> for Linear {
 
 impl Map for Linear {}
 
-impl Instance<MapClass> for Linear {
-    fn source(_: &mut HashSet<u64>) -> String {
-        "#include <clay/map/linear.h>".to_string()
-    }
-    fn inst_name() -> String {
-        "linear".to_string()
-    }
-}
-
 impl Pack for Linear {
     fn size_int() -> usize {
         2*Matrix3::<f64>::size_int()
@@ -35,4 +27,18 @@ impl Pack for Linear {
         .pack(&self.0)
         .pack(&inverse);
     }
+    fn layout() -> Option<Layout> {
+        Some(Layout::structure::<Self>("Linear", "linear", FieldsBuilder::new()
+            .field::<Matrix3<f64>>("linear")
+            .field::<Matrix3<f64>>("inverse")
+            .build()
+        ))
+    }
+}
+
+impl Unpack for Linear {
+    fn unpack_from(buffer_int: &[i32], buffer_float: &[f32]) -> crate::Result<Self> {
+        // Inverse matrix is skipped because it could be computed from the direct one
+        Matrix3::unpack_from(buffer_int, buffer_float).map(Linear)
+    }
 }
@@ -6,3 +6,5 @@ mod linear;
 pub use linear::Linear;
 mod affine;
 pub use affine::Affine;
+mod moving_shift;
+pub use moving_shift::MovingShift;
@@ -0,0 +1,28 @@
+use nalgebra::{Vector3};
+use clay_core::{pack::*, class::*, map::*};
+use super::Shift;
+
+
+/// Shift moving linearly from `start` at time `0` to `end` at time `1`.
+///
+/// The offset is clamped outside of this interval.
+/// Rendered with the worker shutter open, the mapped object is motion blurred.
+#[derive(Pack, Unpack, Instance)]
+#[instance(class = "MapClass", source = "clay/map/moving_shift.h", name = "moving_shift")]
+pub struct MovingShift {
+    pub start: Vector3<f64>,
+    pub end: Vector3<f64>,
+}
+
+impl MovingShift {
+    pub fn new(start: Shift, end: Shift) -> Self {
+        Self { start: start.0, end: end.0 }
+    }
+
+    /// Offset at the specified time.
+    pub fn offset(&self, time: f64) -> Vector3<f64> {
+        self.start + (self.end - self.start)*time.clamp(0.0, 1.0)
+    }
+}
+
+impl Map for MovingShift {}
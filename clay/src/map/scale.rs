@@ -1,8 +1,9 @@
-use std::collections::HashSet;
 use clay_core::{pack::*, class::*, map::*};
 
 
-pub struct Scale(pub f64);
+#[derive(Pack, Unpack, Instance)]
+#[instance(class = "MapClass", source = "clay/map/scale.h", name = "scale")]
+pub struct Scale(#[pack(name = "factor")] pub f64);
 
 impl From<f64> for Scale {
     fn from(x: f64) -> Self {
@@ -11,24 +12,3 @@ impl From<f64> for Scale {
 }
 
 impl Map for Scale {}
-
-impl Instance<MapClass> for Scale {
-    fn source(_: &mut HashSet<u64>) -> String {
-        "#include <clay/map/scale.h>".to_string()
-    }
-    fn inst_name() -> String {
-        "scale".to_string()
-    }
-}
-
-impl Pack for Scale {
-    fn size_int() -> usize {
-        0
-    }
-    fn size_float() -> usize {
-        1
-    }
-    fn pack_to(&self, _buffer_int: &mut [i32], buffer_float: &mut [f32]) {
-        buffer_float.pack(&self.0);
-    }
-}
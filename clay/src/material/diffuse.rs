@@ -1,8 +1,8 @@
-use std::collections::HashSet;
-use clay_core::{pack::*, class::*, material::*};
+use clay_core::{pack::*, class::*, material::*, Editable};
 
 
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug, Default, Pack, Unpack, Instance)]
+#[instance(class = "MaterialClass", source = "clay/material/diffuse.h", name = "diffuse")]
 pub struct Diffuse {}
 
 impl Material for Diffuse {
@@ -11,17 +11,4 @@ impl Material for Diffuse {
     }
 }
 
-impl Instance<MaterialClass> for Diffuse {
-    fn source(_: &mut HashSet<u64>) -> String {
-        "#include <clay/material/diffuse.h>".to_string()
-    }
-    fn inst_name() -> String {
-        "diffuse".to_string()
-    }
-}
-
-impl Pack for Diffuse {
-    fn size_int() -> usize { 0 }
-    fn size_float() -> usize { 0 }
-    fn pack_to(&self, _buffer_int: &mut [i32], _buffer_float: &mut [f32]) {}
-}
+impl Editable for Diffuse {}
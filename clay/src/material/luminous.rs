@@ -1,8 +1,8 @@
-use std::collections::HashSet;
-use clay_core::{pack::*, class::*, material::*};
+use clay_core::{pack::*, class::*, material::*, Editable};
 
 
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug, Default, Pack, Unpack, Instance)]
+#[instance(class = "MaterialClass", source = "clay/material/luminous.h", name = "luminous")]
 pub struct Luminous {}
 
 impl Material for Luminous {
@@ -11,17 +11,4 @@ impl Material for Luminous {
     }
 }
 
-impl Instance<MaterialClass> for Luminous {
-    fn source(_: &mut HashSet<u64>) -> String {
-        "#include <clay/material/luminous.h>".to_string()
-    }
-    fn inst_name() -> String {
-        "luminous".to_string()
-    }
-}
-
-impl Pack for Luminous {
-    fn size_int() -> usize { 0 }
-    fn size_float() -> usize { 0 }
-    fn pack_to(&self, _buffer_int: &mut [i32], _buffer_float: &mut [f32]) {}
-}
+impl Editable for Luminous {}
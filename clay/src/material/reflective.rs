@@ -1,8 +1,8 @@
-use std::collections::HashSet;
-use clay_core::{pack::*, class::*, material::*};
+use clay_core::{pack::*, class::*, material::*, Editable};
 
 
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug, Default, Pack, Unpack, Instance)]
+#[instance(class = "MaterialClass", source = "clay/material/reflective.h", name = "reflective")]
 pub struct Reflective {}
 
 impl Material for Reflective {
@@ -11,17 +11,4 @@ impl Material for Reflective {
     }
 }
 
-impl Instance<MaterialClass> for Reflective {
-    fn source(_: &mut HashSet<u64>) -> String {
-    	"#include <clay/material/reflective.h>".to_string()
-    }
-    fn inst_name() -> String {
-        "reflective".to_string()
-    }
-}
-
-impl Pack for Reflective {
-    fn size_int() -> usize { 0 }
-    fn size_float() -> usize { 0 }
-    fn pack_to(&self, _buffer_int: &mut [i32], _buffer_float: &mut [f32]) {}
-}
+impl Editable for Reflective {}
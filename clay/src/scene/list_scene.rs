@@ -1,4 +1,7 @@
-use std::collections::HashSet;
+use std::{
+    collections::HashSet,
+    marker::PhantomData,
+};
 use ocl::{
     self,
     builders::KernelBuilder,
@@ -9,47 +12,61 @@ use clay_core::{
     class::*,
     object::*,
     Background,
+    Editable, EditableScene, Edit, HostRay, pick,
 };
-use clay_core::{Push, Scene};
+use clay_core::{Push, Scene, Space, Euclidean};
 
 
 #[allow(dead_code)]
-pub struct ListSceneBuilder<O: Object, B: Background> {
+pub struct ListSceneBuilder<O: Object, B: Background, P: Space = Euclidean> {
     objects: Vec<O>,
     background: B,
+    phantom: PhantomData<P>,
 }
 
-impl<O: Object, B: Background> ListSceneBuilder<O, B> {
+impl<O: Object, B: Background, P: Space> ListSceneBuilder<O, B, P> {
     pub fn add(&mut self, object: O) -> &mut Self {
         self.objects.push(object);
         self
     }
-    pub fn build(self, context: &Context) -> crate::Result<ListScene<O, B>> {
+    pub fn build(self, context: &Context) -> crate::Result<ListScene<O, B, P>> {
         ListScene::new(context, self.objects, self.background)
     }
 }
 
-pub struct ListScene<O: Object, B: Background> {
+/// List of objects traced in the space `P`.
+///
+/// Rays travel along geodesics of the space,
+/// the background is looked up by `space_background_dir`.
+pub struct ListScene<O: Object, B: Background, P: Space = Euclidean> {
+    objects: Vec<O>,
     buffer: InstanceBuffer<O>,
     background: B,
+    phantom: PhantomData<P>,
 }
 
-impl<O: Object, B: Background> ListScene<O, B> {
+impl<O: Object, B: Background, P: Space> ListScene<O, B, P> {
     pub fn new(
         context: &Context,
         objects: Vec<O>,
         background: B,
     ) -> crate::Result<Self> {
         let buffer = InstanceBuffer::new(context, &objects)?;
-        Ok(Self { buffer, background })
+        Ok(Self { objects, buffer, background, phantom: PhantomData })
     }
 
-    pub fn builder(background: B) -> ListSceneBuilder<O, B> {
-        ListSceneBuilder { objects: Vec::new(), background } 
+    pub fn objects(&self) -> &[O] {
+        &self.objects
+    }
+
+    pub fn builder(background: B) -> ListSceneBuilder<O, B, P> {
+        ListSceneBuilder { objects: Vec::new(), background, phantom: PhantomData }
     }
 }
 
-impl<O: Object, B: Background> Scene for ListScene<O, B> {
+impl<O: Object, B: Background, P: Space> Scene for ListScene<O, B, P> {
+    type Space = P;
+
     fn source(cache: &mut HashSet<u64>) -> String {
         // TODO: iterate over class methods
         [
@@ -67,7 +84,21 @@ impl<O: Object, B: Background> Scene for ListScene<O, B> {
     }
 }
 
-impl<O: Object, B: Background> Push for ListScene<O, B> {
+impl<O: Object + Editable, B: Background> EditableScene for ListScene<O, B, Euclidean> {
+    fn pick(&self, ray: &HostRay) -> Option<usize> {
+        pick(&self.objects, ray)
+    }
+    fn edit(&mut self, index: usize, edit: &Edit) -> crate::Result<bool> {
+        let object = self.objects.get_mut(index).ok_or("object index out of bounds")?;
+        if !object.edit(edit) {
+            return Ok(false);
+        }
+        self.buffer.write_at(index, &self.objects[index..(index + 1)])?;
+        Ok(true)
+    }
+}
+
+impl<O: Object, B: Background, P: Space> Push for ListScene<O, B, P> {
     fn args_def(kb: &mut KernelBuilder) {
         InstanceBuffer::<O>::args_def(kb);
         B::args_def(kb);
@@ -10,9 +10,11 @@ use clay_core::{
     shape::*,
     object::*,
     buffer::InstanceBuffer,
+    layout::*,
     Background,
+    Editable, EditableScene, Edit, HostRay, pick,
 };
-use clay_core::{Push, Scene};
+use clay_core::{Push, Scene, Euclidean};
 
 struct TargetData<T: Target> {
     object_index: usize,
@@ -35,6 +37,14 @@ impl<T: Target> Pack for TargetData<T> {
             &mut buffer_float[1..],
         );
     }
+    fn layout() -> Option<Layout> {
+        Some(Layout::structure::<Self>("TargetData", "target_data", FieldsBuilder::new()
+            .field::<i32>("object_index")
+            .field::<f32>("brightness")
+            .field::<T>("target")
+            .build()
+        ))
+    }
 }
 
 struct ObjectData<O: Object> {
@@ -59,6 +69,13 @@ impl<O: Object> Pack for ObjectData<O> {
             buffer_float,
         );
     }
+    fn layout() -> Option<Layout> {
+        Some(Layout::structure::<Self>("ObjectData", "object_data", FieldsBuilder::new()
+            .field::<i32>("target_index")
+            .field::<O>("object")
+            .build()
+        ))
+    }
 }
 
 
@@ -87,6 +104,8 @@ impl<O: Object + Targeted<T>, T: Target, B: Background> TargetListSceneBuilder<O
 }
 
 pub struct TargetListScene<O: Object + Targeted<T>, T: Target, B: Background> {
+    objects: Vec<ObjectData<O>>,
+    targets: Vec<TargetData<T>>,
     object_buffer: InstanceBuffer<ObjectData<O>>,
     target_buffer: InstanceBuffer<TargetData<T>>,
     background: B,
@@ -122,7 +141,11 @@ impl<O: Object + Targeted<T>, T: Target, B: Background> TargetListScene<O, T, B>
         }
         let object_buffer = InstanceBuffer::new(context, &objects)?;
         let target_buffer = InstanceBuffer::new(context, &targets)?;
-        Ok(Self { object_buffer, target_buffer, background })
+        Ok(Self { objects, targets, object_buffer, target_buffer, background })
+    }
+
+    pub fn objects(&self) -> impl Iterator<Item=&O> {
+        self.objects.iter().map(|data| &data.object)
     }
 
     pub fn builder(background: B) -> TargetListSceneBuilder<O, T, B> {
@@ -131,12 +154,16 @@ impl<O: Object + Targeted<T>, T: Target, B: Background> TargetListScene<O, T, B>
 }
 
 impl<O: Object + Targeted<T>, T: Target, B: Background> Scene for TargetListScene<O, T, B> {
+    type Space = Euclidean;
+
     fn source(cache: &mut HashSet<u64>) -> String {
         // TODO: iterate over class methods
         [
             O::source(cache),
             T::source(cache),
             B::source(cache),
+            layout_source::<ObjectData<O>>(),
+            layout_source::<TargetData<T>>(),
             ObjectClass::methods().into_iter().map(|method| {
                 format!(
                     "#define __object_{} {}_{}",
@@ -155,6 +182,30 @@ impl<O: Object + Targeted<T>, T: Target, B: Background> Scene for TargetListScen
     }
 }
 
+impl<
+    O: Object + Targeted<T> + Editable, T: Target, B: Background,
+> EditableScene for TargetListScene<O, T, B> {
+    fn pick(&self, ray: &HostRay) -> Option<usize> {
+        pick(self.objects(), ray)
+    }
+    fn edit(&mut self, index: usize, edit: &Edit) -> crate::Result<bool> {
+        let data = self.objects.get_mut(index).ok_or("object index out of bounds")?;
+        if !data.object.edit(edit) {
+            return Ok(false);
+        }
+        // The target is derived from the object so it is updated too
+        if let Some(ti) = data.target_index {
+            if let Some((target, brightness)) = data.object.target() {
+                self.targets[ti].target = target;
+                self.targets[ti].brightness = brightness;
+                self.target_buffer.write_at(ti, &self.targets[ti..(ti + 1)])?;
+            }
+        }
+        self.object_buffer.write_at(index, &self.objects[index..(index + 1)])?;
+        Ok(true)
+    }
+}
+
 impl<O: Object + Targeted<T>, T: Target, B: Background> Push for TargetListScene<O, T, B> {
     fn args_def(kb: &mut KernelBuilder) {
         InstanceBuffer::<ObjectData<O>>::args_def(kb);
@@ -5,6 +5,7 @@ use clay_core::{
     class::*,
     map::*,
     shape::*,
+    Editable, Edit, HostRay,
 };
 use crate::{
     map::{Linear, Shift, Affine},
@@ -12,6 +13,7 @@ use crate::{
 };
 
 type EllipsoidBase = ShapeMapper<UnitSphere, Affine>;
+#[derive(Pack, Unpack)]
 pub struct Ellipsoid(EllipsoidBase);
 
 impl Ellipsoid {
@@ -32,14 +34,6 @@ impl Instance<ShapeClass> for Ellipsoid {
     fn inst_name() -> String { EllipsoidBase::inst_name() }
 }
 
-impl Pack for Ellipsoid {
-    fn size_int() -> usize { EllipsoidBase::size_int() }
-    fn size_float() -> usize { EllipsoidBase::size_float() }
-    fn pack_to(&self, buffer_int: &mut [i32], buffer_float: &mut [f32]) {
-        self.0.pack_to(buffer_int, buffer_float);
-    }
-}
-
 impl Bounded<Sphere> for Ellipsoid {
     fn bound(&self) -> Option<Sphere> {
         let rad = SVD::new(
@@ -51,3 +45,20 @@ impl Bounded<Sphere> for Ellipsoid {
         Some(Sphere::new(rad, self.0.map.second.0))
     }
 }
+
+impl Editable for Ellipsoid {
+    fn bounding_sphere(&self) -> Option<(Vector3<f64>, f64)> {
+        self.bound().and_then(|sphere: Sphere| sphere.bounding_sphere())
+    }
+    fn hit(&self, ray: &HostRay) -> Option<f64> {
+        ray.hit_ellipsoid(&self.0.map.first.0, &self.0.map.second.0)
+    }
+    fn edit(&mut self, edit: &Edit) -> bool {
+        match edit {
+            Edit::Translate(shift) => self.0.map.second.0 += shift,
+            Edit::Scale(factor) => self.0.map.first.0 *= *factor,
+            _ => return false,
+        }
+        true
+    }
+}
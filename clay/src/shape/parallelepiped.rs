@@ -5,6 +5,7 @@ use clay_core::{
     class::*,
     map::*,
     shape::*,
+    Editable, Edit, HostRay,
 };
 use crate::{
     map::{Linear, Shift, Affine},
@@ -13,6 +14,7 @@ use crate::{
 
 
 type ParallelepipedBase = ShapeMapper<UnitCube, Affine>;
+#[derive(Pack, Unpack)]
 pub struct Parallelepiped(pub ParallelepipedBase);
 
 impl Parallelepiped {
@@ -35,26 +37,18 @@ impl Instance<ShapeClass> for Parallelepiped {
         ParallelepipedBase::inst_name()
     }
 }
-impl Pack for Parallelepiped {
-    fn size_int() -> usize { ParallelepipedBase::size_int() }
-    fn size_float() -> usize { ParallelepipedBase::size_float() }
-    fn pack_to(&self, buffer_int: &mut [i32], buffer_float: &mut [f32]) {
-        self.0.pack_to(buffer_int, buffer_float);
-    }
-}
-
 impl Bounded<Sphere> for Parallelepiped {
     fn bound(&self) -> Option<Sphere> {
         let pos = self.0.map.second.0;
         let ori = self.0.map.first.0;
-        let basis = ori.transpose();
+        // The bounding sphere must contain all the corners, it is also an early-out of picking
         let mut rad = 0.0;
         for i in 0..8 {
             let mut data = [0.0; 3];
             for j in 0..3 {
-                data[j] = 1.0 - 2.0*(((i << j) & 1) as f64);
+                data[j] = 1.0 - 2.0*(((i >> j) & 1) as f64);
             }
-            let len = (basis*Vector3::from_column_slice(&data)).norm();
+            let len = (ori*Vector3::from_column_slice(&data)).norm();
             if len > rad {
                 rad = len;
             }
@@ -62,3 +56,20 @@ impl Bounded<Sphere> for Parallelepiped {
         Some(Sphere::new(rad, pos))
     }
 }
+
+impl Editable for Parallelepiped {
+    fn bounding_sphere(&self) -> Option<(Vector3<f64>, f64)> {
+        self.bound().and_then(|sphere: Sphere| sphere.bounding_sphere())
+    }
+    fn hit(&self, ray: &HostRay) -> Option<f64> {
+        ray.hit_parallelepiped(&self.0.map.first.0, &self.0.map.second.0)
+    }
+    fn edit(&mut self, edit: &Edit) -> bool {
+        match edit {
+            Edit::Translate(shift) => self.0.map.second.0 += shift,
+            Edit::Scale(factor) => self.0.map.first.0 *= *factor,
+            _ => return false,
+        }
+        true
+    }
+}
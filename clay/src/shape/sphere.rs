@@ -5,6 +5,8 @@ use clay_core::{
     class::*,
     map::*,
     shape::*,
+    layout::*,
+    Editable, Edit,
 };
 use crate::{
     map::{Scale, Shift},
@@ -13,6 +15,7 @@ use crate::{
 
 
 type SphereBase = ShapeMapper<UnitSphere, Chain<Scale, Shift>>;
+#[derive(Unpack)]
 pub struct Sphere(pub SphereBase);
 
 impl Sphere {
@@ -26,19 +29,28 @@ impl From<SphereBase> for Sphere {
     }
 }
 
-impl Shape for Sphere {}
-
-impl Instance<ShapeClass> for Sphere {
-    fn source(cache: &mut HashSet<u64>) -> String { SphereBase::source(cache) }
-    fn inst_name() -> String { SphereBase::inst_name() }
-}
-
 impl Pack for Sphere {
     fn size_int() -> usize { SphereBase::size_int() }
     fn size_float() -> usize { SphereBase::size_float() }
     fn pack_to(&self, buffer_int: &mut [i32], buffer_float: &mut [f32]) {
         self.0.pack_to(buffer_int, buffer_float);
     }
+    fn layout() -> Option<Layout> {
+        // Radius and position are stored in `Scale` and `Shift` maps after the unit sphere
+        Some(Layout::structure::<Self>("Sphere", "sphere", FieldsBuilder::new()
+            .skip::<UnitSphere>()
+            .field_in::<Scale, f64>("radius")
+            .field_in::<Shift, Vector3<f64>>("position")
+            .build()
+        ))
+    }
+}
+
+impl Shape for Sphere {}
+
+impl Instance<ShapeClass> for Sphere {
+    fn source(cache: &mut HashSet<u64>) -> String { SphereBase::source(cache) }
+    fn inst_name() -> String { SphereBase::inst_name() }
 }
 
 impl Bound for Sphere {}
@@ -49,6 +61,25 @@ impl Instance<BoundClass> for Sphere {
 
 impl Target for Sphere {}
 impl Instance<TargetClass> for Sphere {
-    fn source(cache: &mut HashSet<u64>) -> String { UnitSphere::source(cache) }
+    fn source(_: &mut HashSet<u64>) -> String {
+        [
+            layout_source::<Self>(),
+            "#include <clay/shape/sphere_target.h>".to_string(),
+        ].join("\n")
+    }
     fn inst_name() -> String { "sphere_target".to_string() }
 }
+
+impl Editable for Sphere {
+    fn bounding_sphere(&self) -> Option<(Vector3<f64>, f64)> {
+        Some((self.0.map.second.0, self.0.map.first.0))
+    }
+    fn edit(&mut self, edit: &Edit) -> bool {
+        match edit {
+            Edit::Translate(shift) => self.0.map.second.0 += shift,
+            Edit::Scale(factor) => self.0.map.first.0 *= factor,
+            _ => return false,
+        }
+        true
+    }
+}
@@ -1,4 +1,3 @@
-use std::collections::HashSet;
 use clay_core::{
     pack::*,
     class::*,
@@ -10,7 +9,8 @@ use clay_core::{
 ///
 /// This shape could be transformed to an arbitrary parallelepiped
 /// by combining with the affine transform  (*see `Shape::map()`*).
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug, Default, Pack, Unpack, Instance)]
+#[instance(class = "ShapeClass", source = "clay/shape/cube.h", name = "cube")]
 pub struct UnitCube {}
 
 impl UnitCube {
@@ -21,18 +21,3 @@ impl UnitCube {
 }
 
 impl Shape for UnitCube {}
-
-impl Instance<ShapeClass> for UnitCube {
-    fn source(_: &mut HashSet<u64>) -> String {
-        "#include <clay/shape/cube.h>".to_string()
-    }
-    fn inst_name() -> String {
-        "cube".to_string()
-    }
-}
-
-impl Pack for UnitCube {
-    fn size_int() -> usize { 0 }
-    fn size_float() -> usize { 0 }
-    fn pack_to(&self, _buffer_int: &mut [i32], _buffer_float: &mut [f32]) {}
-}
@@ -1,4 +1,3 @@
-use std::collections::HashSet;
 use clay_core::{
     pack::*,
     class::*,
@@ -10,7 +9,8 @@ use clay_core::{
 ///
 /// This shape could be transformed to an arbitrary ellipsoid
 /// by combining with the affine transform (*see `Shape::map()`*).
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug, Default, Pack, Unpack, Instance)]
+#[instance(class = "ShapeClass", source = "clay/shape/sphere.h", name = "unit_sphere")]
 pub struct UnitSphere {}
 
 impl UnitSphere {
@@ -18,20 +18,6 @@ impl UnitSphere {
     pub fn new() -> Self {
         Self {}
     }
-    fn source() -> String {
-        "#include <clay/shape/sphere.h>".to_string()
-    }
 }
 
 impl Shape for UnitSphere {}
-
-impl Instance<ShapeClass> for UnitSphere {
-    fn source(_: &mut HashSet<u64>) -> String { Self::source() }
-    fn inst_name() -> String { "unit_sphere".to_string() }
-}
-
-impl Pack for UnitSphere {
-    fn size_int() -> usize { 0 }
-    fn size_float() -> usize { 0 }
-    fn pack_to(&self, _buffer_int: &mut [i32], _buffer_float: &mut [f32]) {}
-}
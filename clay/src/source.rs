@@ -1,19 +1,9 @@
-use std::{
-    path::Path,
-};
 use ocl_include::{MemHook};
+use clay_core::embedded_hook;
 
 include!(concat!(env!("OUT_DIR"), "/ocl_src_list.rs"));
 
 
 pub fn source() -> MemHook {
-    let mut hook = MemHook::new();
-    let pref = Path::new("clay");
-    for (name, content) in OCL_SRC_LIST.iter() {
-        hook.add_file(
-            &pref.join(name),
-            content.to_string(),
-        ).unwrap();
-    }
-    hook
+    embedded_hook("clay", &OCL_SRC_LIST)
 }
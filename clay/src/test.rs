@@ -0,0 +1,208 @@
+use nalgebra::{Vector3, Matrix3};
+use rand::{Rng, thread_rng};
+use clay_core::{
+    pack::*, layout::*,
+    shape::*, material::*, object::*,
+    shape_select, material_select, material_combine,
+    Editable, Edit, PickView, HostRay, pick,
+    Context, Screen, Adaptive,
+};
+use crate::{
+    shape::*, material::*, map::*,
+    view::ProjView,
+    scene::ListScene,
+    background::ConstantBackground,
+    DefaultWorker,
+};
+
+
+shape_select!(TestShape {
+    Cube(TC = Parallelepiped),
+    Sphere(TS = Ellipsoid),
+});
+material_combine!(TestGlossy {
+    reflect: Reflective,
+    diffuse: Colored<Diffuse>,
+});
+material_select!(TestMaterial {
+    Matte(TM = Colored<Diffuse>),
+    Glossy(TG = TestGlossy),
+    Luminous(TL = Colored<Luminous>),
+});
+type TestObject = Covered<TestShape, TestMaterial>;
+
+
+fn rand_vector<R: Rng>(rng: &mut R) -> Vector3<f64> {
+    Vector3::from_fn(|_, _| rng.gen_range(-1.0, 1.0))
+}
+
+fn rand_matrix<R: Rng>(rng: &mut R) -> Matrix3<f64> {
+    // Add diagonal term to keep matrix invertible
+    Matrix3::from_fn(|_, _| rng.gen_range(-1.0, 1.0)) + 4.0*Matrix3::identity()
+}
+
+fn rand_object<R: Rng>(rng: &mut R) -> TestObject {
+    let shape = if rng.gen() {
+        TestShape::from(Parallelepiped::new(rand_matrix(rng), rand_vector(rng)))
+    } else {
+        TestShape::from(Ellipsoid::new(rand_matrix(rng), rand_vector(rng)))
+    };
+    let material = match rng.gen_range(0, 3) {
+        0 => TestMaterial::from(Diffuse {}.color_with(rand_vector(rng))),
+        1 => TestMaterial::from(TestGlossy::new(
+            (rng.gen(), Reflective {}),
+            (rng.gen(), Diffuse {}.color_with(rand_vector(rng))),
+        )),
+        _ => TestMaterial::from(Luminous {}.color_with(rand_vector(rng))),
+    };
+    shape.cover(material)
+}
+
+fn pack<T: Pack>(object: &T) -> (Vec<i32>, Vec<f32>) {
+    let mut buffer_int = vec![0; T::size_int()];
+    let mut buffer_float = vec![0.0; T::size_float()];
+    object.pack_to(&mut buffer_int, &mut buffer_float);
+    (buffer_int, buffer_float)
+}
+
+fn assert_round_trip<T: Pack + Unpack>(object: &T) {
+    let (buffer_int, buffer_float) = pack(object);
+    let (repacked_int, repacked_float) = pack(&T::unpack_from(&buffer_int, &buffer_float).unwrap());
+    assert_eq!(buffer_int, repacked_int);
+    for (a, b) in buffer_float.iter().zip(repacked_float.iter()) {
+        // Inverse matrix of `Linear` is recomputed so the values could slightly differ
+        assert!((a - b).abs() <= 1e-4*a.abs().max(1.0), "{} != {}", a, b);
+    }
+}
+
+#[test]
+fn round_trip_sphere() {
+    let mut rng = thread_rng();
+    for _ in 0..64 {
+        let sphere = Sphere::new(rng.gen(), rand_vector(&mut rng));
+        assert_round_trip(&sphere);
+        assert_round_trip(&sphere.cover(Diffuse {}.color_with(rand_vector(&mut rng))));
+    }
+}
+
+#[test]
+fn round_trip_objects() {
+    let mut rng = thread_rng();
+    for _ in 0..256 {
+        assert_round_trip(&rand_object(&mut rng));
+    }
+}
+
+#[test]
+fn layout_sphere() {
+    let source = layout_source::<Sphere>();
+    assert!(source.contains("#define SPHERE_RADIUS_DF 0"));
+    assert!(source.contains("#define SPHERE_POSITION_DF 1"));
+    assert!(source.contains(".position = vload3(0, (fbuf + SPHERE_POSITION_DF))"));
+}
+
+#[test]
+fn layout_linear() {
+    let source = layout_source::<Linear>();
+    assert!(source.contains("#define LINEAR_SIZE_FLOAT 18"));
+    assert!(source.contains("#define LINEAR_INVERSE_DF 9"));
+    assert!(source.contains(".inverse = matrix3_load((fbuf + LINEAR_INVERSE_DF))"));
+}
+
+#[test]
+fn layout_derived() {
+    let source = layout_source::<Shift>();
+    assert!(source.contains("#define SHIFT_OFFSET_DF 0"));
+    assert!(source.contains("\tfloat3 offset;"));
+    assert!(source.contains("Shift shift_load("));
+}
+
+#[test]
+fn layout_moving_shift() {
+    let source = layout_source::<MovingShift>();
+    assert!(source.contains("#define MOVING_SHIFT_START_DF 0"));
+    assert!(source.contains("#define MOVING_SHIFT_END_DF 3"));
+    assert!(source.contains("MovingShift moving_shift_load("));
+
+    let shift = MovingShift::new(Shift::from(Vector3::zeros()), Shift::from(Vector3::new(2.0, 0.0, 0.0)));
+    assert_eq!(shift.offset(0.5), Vector3::new(1.0, 0.0, 0.0));
+    assert_eq!(shift.offset(2.0), Vector3::new(2.0, 0.0, 0.0));
+}
+
+#[test]
+fn pick_and_edit() {
+    let view = ProjView { pos: Vector3::zeros(), ori: Matrix3::identity() };
+    let ray = view.pick_ray((50, 50), (100, 100));
+    assert!((ray.dir - Vector3::new(0.0, 0.0, -1.0)).norm() < 1e-9);
+
+    let mut object = Sphere::new(1.0, Vector3::new(0.0, 0.0, -3.0))
+    .cover(Diffuse {}.color_with(Vector3::new(0.5, 0.5, 0.5)));
+    let (center, radius) = object.bounding_sphere().unwrap();
+    assert_eq!(ray.hit_sphere(&center, radius), Some(2.0));
+
+    assert!(object.edit(&Edit::Translate(Vector3::new(1.0, 0.0, 0.0))));
+    assert!(object.edit(&Edit::Scale(2.0)));
+    assert!(object.edit(&Edit::Recolor(Vector3::new(1.0, 0.0, -0.1))));
+    assert_eq!(object.bounding_sphere(), Some((Vector3::new(1.0, 0.0, -3.0), 2.0)));
+    assert_eq!(object.material.color, Vector3::new(1.0, 0.5, 0.4));
+}
+
+#[test]
+fn pick_exact() {
+    let ray = HostRay::new(Vector3::zeros(), Vector3::new(0.0, 0.0, -1.0));
+    let flat = Matrix3::from_diagonal(&Vector3::new(2.0, 2.0, 0.1));
+    // Flat shapes in front of the ray
+    let near_cube = Parallelepiped::new(flat, Vector3::new(0.0, 0.5, -3.0));
+    let near_ball = Ellipsoid::new(flat, Vector3::new(0.0, 0.5, -3.0));
+    let far = Parallelepiped::new(Matrix3::identity(), Vector3::new(0.0, 0.0, -6.0));
+    let round = |d: Option<f64>| d.map(|d| (d*1e6).round()/1e6);
+    assert_eq!(round(near_cube.hit(&ray)), Some(2.9));
+    assert_eq!(round(near_ball.hit(&ray)), Some(2.9));
+
+    let above = HostRay::new(Vector3::new(0.0, 0.5, 0.0), Vector3::new(0.0, 1.0, -1.0));
+    // The bounding sphere of the flat box is hit, but the box itself is not
+    let (center, radius) = near_cube.bounding_sphere().unwrap();
+    assert!(above.hit_sphere(&center, radius).is_some());
+    assert_eq!(near_cube.hit(&above), None);
+    assert_eq!(near_ball.hit(&above), None);
+
+    assert_eq!(pick(&[near_cube, far], &above), None);
+    let thin = Parallelepiped::new(flat, Vector3::new(0.0, 2.5, -3.0));
+    let far = Parallelepiped::new(Matrix3::identity(), Vector3::new(0.0, 0.0, -6.0));
+    assert_eq!(pick(&[thin, far], &ray), Some(1));
+}
+
+/// Context on the first available device, `None` skips the test if there is no OpenCL.
+fn test_context() -> Option<Context> {
+    let platform = ocl::Platform::new(*ocl::core::get_platform_ids().ok()?.first()?);
+    let device = ocl::Device::first(platform).ok()?;
+    Context::new(platform, device).ok()
+}
+
+#[test]
+fn adaptive_cropped_tile() {
+    let context = match test_context() {
+        Some(context) => context,
+        None => return,
+    };
+    type TestScene = ListScene<Covered<Sphere, Colored<Diffuse>>, ConstantBackground>;
+    let mut worker = DefaultWorker::<TestScene, ProjView>::builder().unwrap().build(&context).unwrap();
+    worker.set_adaptive(Adaptive { enabled: true, threshold: 0.01, min_samples: 4 });
+
+    // The sphere is behind the camera, so all pixels have the constant background color and converge
+    let mut scene = ListScene::builder(ConstantBackground::new(Vector3::new(0.5, 0.5, 0.5)));
+    scene.add(
+        Sphere::new(1.0, Vector3::new(0.0, 0.0, 4.0))
+        .cover(Diffuse {}.color_with(Vector3::new(0.5, 0.5, 0.5)))
+    );
+    let scene = scene.build(&context).unwrap();
+    let view = ProjView { pos: Vector3::zeros(), ori: Matrix3::identity() };
+
+    // The frame is not a multiple of the tile size, so the last tile is cropped to 2x3
+    let mut screen = Screen::new(&context, (4, 4)).unwrap();
+    let n_passes = 64;
+    let image = worker.render_tiled(&mut screen, (10, 7), n_passes, &scene, &view, |_, _| ()).unwrap();
+    assert_eq!(image.len(), 3*10*7);
+    assert_eq!(screen.unconverged().unwrap(), 0);
+    assert!(screen.n_passes() < n_passes);
+}
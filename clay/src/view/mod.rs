@@ -1,2 +1,4 @@
 mod proj_view;
 pub use proj_view::ProjView;
+mod model_view;
+pub use model_view::ModelView;
@@ -0,0 +1,54 @@
+use std::{collections::HashSet, marker::PhantomData};
+use ocl::{self, builders::KernelBuilder};
+use nalgebra::{Vector3, Matrix3};
+use clay_core::{Push, View, Space};
+use crate::view::ProjView;
+
+
+/// Pinhole camera in the conformal model of the curved space `P`.
+///
+/// The camera is placed at `pos` of the model and `ori` columns are its axes.
+/// Since the model is conformal, rays are emitted the same way as by `ProjView`.
+pub struct ModelView<P: Space> {
+    pub pos: Vector3<f64>,
+    pub ori: Matrix3<f64>,
+    phantom: PhantomData<P>,
+}
+
+impl<P: Space> ModelView<P> {
+    pub fn new(pos: Vector3<f64>, ori: Matrix3<f64>) -> Self {
+        assert!(P::contains(&pos), "camera must be inside the model of the space");
+        Self { pos, ori, phantom: PhantomData }
+    }
+
+    /// Places the camera at the distance of `pos` length from the origin in its direction.
+    ///
+    /// Useful to control the camera by Euclidean motion, e.g. in `clay_gui::Window`.
+    pub fn from_tangent(pos: Vector3<f64>, ori: Matrix3<f64>) -> Self {
+        Self::new(P::exp(&pos), ori)
+    }
+
+    fn proj(&self) -> ProjView {
+        ProjView { pos: self.pos, ori: self.ori }
+    }
+}
+
+impl<P: Space> View for ModelView<P> {
+    type Space = P;
+
+    fn source(cache: &mut HashSet<u64>) -> String {
+        ProjView::source(cache)
+    }
+}
+
+impl<P: Space> Push for ModelView<P> {
+    fn args_def(kb: &mut KernelBuilder) {
+        ProjView::args_def(kb);
+    }
+    fn args_set(&self, i: usize, k: &mut ocl::Kernel) -> crate::Result<()> {
+        self.proj().args_set(i, k)
+    }
+    fn args_count() -> usize {
+        ProjView::args_count()
+    }
+}
@@ -1,7 +1,7 @@
 use std::collections::HashSet;
 use ocl::{self, prm, builders::KernelBuilder};
 use nalgebra::{Vector3, Matrix3};
-use clay_core::{Push, View};
+use clay_core::{Push, View, PickView, HostRay, Euclidean};
 
 pub struct ProjView {
     pub pos: Vector3<f64>,
@@ -9,11 +9,23 @@ pub struct ProjView {
 }
 
 impl View for ProjView {
+	type Space = Euclidean;
+
 	fn source(_: &mut HashSet<u64>) -> String {
 		"#include <clay/view/proj_view.h>\n".to_string()
 	}
 }
 
+impl PickView for ProjView {
+    fn pick_ray(&self, pixel: (usize, usize), dims: (usize, usize)) -> HostRay {
+        // Same projection as in `__view_emit` without the random offset
+        let x = (pixel.0 as f64 - 0.5*dims.0 as f64)/dims.1 as f64;
+        let y = -(pixel.1 as f64 - 0.5*dims.1 as f64)/dims.1 as f64;
+        let (ori, pos) = (&self.ori, self.pos);
+        HostRay::new(pos, x*ori.column(0) + y*ori.column(1) - ori.column(2))
+    }
+}
+
 impl Push for ProjView {
     fn args_def(kb: &mut KernelBuilder) {
         kb
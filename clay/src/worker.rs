@@ -1,11 +1,12 @@
 use std::marker::PhantomData;
 use clay_core::{Scene, View, worker::*};
 
+/// Worker with the sources of `clay`, the sources of the scene space are added by `Worker::builder`.
 pub struct DefaultWorker<S, V> {
     phantom: PhantomData<(S, V)>
 }
 
-impl<S: Scene, V: View> DefaultWorker<S, V> {
+impl<S: Scene, V: View<Space = S::Space>> DefaultWorker<S, V> {
     pub fn builder() -> crate::Result<WorkerBuilder<S, V>> {
         let mut builder = Worker::<S, V>::builder();
         builder.add_hook(crate::source());